@@ -0,0 +1,26 @@
+//! Conversion of large, mostly valid buffers, where the time goes on
+//! validating the utf8. Compare `cargo bench` with `cargo bench --features simd`.
+
+use std::{hint::black_box,time::Instant};
+
+use to_string::IntoStringNoBom;
+
+const ROUNDS: u32 = 200;
+
+fn bench(name: &str, mut buffer: Vec<u8>) {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        // the buffer is kept when valid, so this is only the validation
+        buffer = black_box(buffer).into_string_no_bom().into_bytes();
+    }
+    let per_round = start.elapsed() / ROUNDS;
+    let mb_per_s = buffer.len() as f64 / per_round.as_secs_f64() / 1e6;
+    println!("{name:<12} {per_round:>12?}/iter {mb_per_s:>10.0} MB/s");
+}
+
+fn main() {
+    let line = "2024-01-01T00:00:00Z INFO request served path=/caf\u{e9} status=200 \u{1F600}\n";
+    let text = line.repeat(4 << 20 >> 6);
+    bench("ascii", "x".repeat(text.len()).into_bytes());
+    bench("mixed", text.into_bytes());
+}
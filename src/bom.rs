@@ -0,0 +1,198 @@
+//! Byte input which may start with a byte order mark.
+
+use alloc::{
+    borrow::Cow,
+    string::String,
+    vec::Vec,
+};
+
+use super::{local_to_cow,local_vec_to_str};
+
+/// Converts `bytes`, decoding them as UTF-16 when they start with a UTF-16
+/// byte order mark.
+///
+/// * `FF FE` is UTF-16LE and `FE FF` is UTF-16BE, unpaired surrogates and
+///   an odd byte left at the end become `U+FFFD`
+/// * anything else is utf8 with the usual lossy rules, including `EF BB BF`
+///
+/// The byte order mark itself (any of the three) is not part of the output.
+/// This is opt in, the plain `IntoString` impls never look for a BOM.
+pub fn bytes_into_string_bom_aware(bytes: &[u8]) -> String {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => utf16_to_str(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16_to_str(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => local_to_cow("[u8]", rest).into_owned(),
+        _ => local_to_cow("[u8]", bytes).into_owned(),
+    }
+}
+
+/// Conversion of byte buffers which may start with a byte order mark,
+/// see [`bytes_into_string_bom_aware`].
+pub trait IntoStringBomAware {
+    fn into_string_bom_aware(self) -> String;
+}
+
+impl IntoStringBomAware for &[u8] {
+    fn into_string_bom_aware(self) -> String {
+        bytes_into_string_bom_aware(self)
+    }
+}
+impl IntoStringBomAware for Vec<u8> {
+    /// Special Case
+    ///
+    /// utf8 keeps the buffer when it is valid, the BOM is removed in place
+    fn into_string_bom_aware(mut self) -> String {
+        match self.as_slice() {
+            [0xFF, 0xFE, ..] | [0xFE, 0xFF, ..] => bytes_into_string_bom_aware(&self),
+            [0xEF, 0xBB, 0xBF, ..] => {
+                self.drain(..3);
+                local_vec_to_str("Vec<u8>", self)
+            }
+            _ => local_vec_to_str("Vec<u8>", self),
+        }
+    }
+}
+impl IntoStringBomAware for &Vec<u8> {
+    fn into_string_bom_aware(self) -> String {
+        bytes_into_string_bom_aware(self)
+    }
+}
+
+/// Converts `bytes` like `String::from_utf8_lossy`, without a leading
+/// utf8 byte order mark `EF BB BF`.
+///
+/// Only the first one goes, a BOM later in the input is kept, and input
+/// which is only a BOM becomes empty. This is the byte version of
+/// [`into_string_no_bom`](crate::IntoStringExt::into_string_no_bom).
+pub fn bytes_into_string_no_bom(bytes: &[u8]) -> String {
+    match local_to_cow("[u8]", bytes) {
+        Cow::Borrowed(text) => String::from(text.strip_prefix('\u{FEFF}').unwrap_or(text)),
+        Cow::Owned(mut text) => {
+            drop_bom(&mut text);
+            text
+        }
+    }
+}
+
+/// Conversion of byte buffers without a leading utf8 byte order mark,
+/// see [`bytes_into_string_no_bom`].
+pub trait IntoStringNoBom {
+    fn into_string_no_bom(self) -> String;
+}
+
+impl IntoStringNoBom for &[u8] {
+    fn into_string_no_bom(self) -> String {
+        bytes_into_string_no_bom(self)
+    }
+}
+impl IntoStringNoBom for Vec<u8> {
+    /// Special Case
+    ///
+    /// The buffer is kept when it is valid, the BOM is removed in place
+    fn into_string_no_bom(self) -> String {
+        let mut text = local_vec_to_str("Vec<u8>", self);
+        drop_bom(&mut text);
+        text
+    }
+}
+impl IntoStringNoBom for &Vec<u8> {
+    fn into_string_no_bom(self) -> String {
+        bytes_into_string_no_bom(self)
+    }
+}
+
+// the BOM is only removed after the conversion, so the offsets reported
+// for bad bytes count from the start of the input, the BOM included
+fn drop_bom(text: &mut String) {
+    if text.starts_with('\u{FEFF}') {
+        text.drain(..'\u{FEFF}'.len_utf8());
+    }
+}
+
+// a trailing odd byte is half a code unit, it becomes one `U+FFFD`
+fn utf16_to_str(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let pairs = bytes.chunks_exact(2);
+    let odd = !pairs.remainder().is_empty();
+    let mut out: String = char::decode_utf16(pairs.map(|x| unit([x[0], x[1]])))
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect();
+    if odd {
+        out.push(char::REPLACEMENT_CHARACTER);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn no_bom_only_the_first() {
+        assert_eq!(bytes_into_string_no_bom(b"\xEF\xBB\xBFtext"), "text");
+        assert_eq!(bytes_into_string_no_bom(b"\xEF\xBB\xBF\xEF\xBB\xBF"), "\u{FEFF}");
+        assert_eq!(bytes_into_string_no_bom(b"te\xEF\xBB\xBFxt"), "te\u{FEFF}xt");
+        assert_eq!(bytes_into_string_no_bom(b"\xEF\xBB\xBF"), "");
+        // half a BOM is just bad bytes
+        assert_eq!(bytes_into_string_no_bom(b"\xEF\xBBtext"), "\u{FFFD}text");
+        let bytes = vec![0xEF, 0xBB, 0xBF, b'a', 0xFF];
+        assert_eq!((&bytes).into_string_no_bom(), "a\u{FFFD}");
+        assert_eq!(bytes.as_slice().into_string_no_bom(), "a\u{FFFD}");
+        assert_eq!(bytes.into_string_no_bom(), "a\u{FFFD}");
+    }
+
+    #[test]
+    fn no_bom_keeps_the_buffer() {
+        let bytes = b"\xEF\xBB\xBFvalid".to_vec();
+        let ptr = bytes.as_ptr();
+        let text = bytes.into_string_no_bom();
+        assert_eq!(text, "valid");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    // without a BOM nothing changes
+    #[test]
+    fn no_bom_without_one() {
+        for bytes in TEST_BYTES {
+            let expected = String::from_utf8_lossy(bytes);
+            assert_eq!(bytes_into_string_no_bom(bytes), expected);
+            assert_eq!(bytes.to_vec().into_string_no_bom(), expected);
+        }
+    }
+
+    #[test]
+    fn utf16_little_and_big_endian() {
+        assert_eq!(bytes_into_string_bom_aware(b"\xFF\xFEh\x00\xE9\x00"), "h\u{e9}");
+        assert_eq!(bytes_into_string_bom_aware(b"\xFE\xFF\x00h\x00\xE9"), "h\u{e9}");
+        // a surrogate pair, then a lone high surrogate
+        assert_eq!(bytes_into_string_bom_aware(b"\xFF\xFE\x3D\xD8\x00\xDE\x3D\xD8"), "\u{1F600}\u{FFFD}");
+        assert_eq!(bytes_into_string_bom_aware(b"\xFF\xFE"), "");
+        let bytes = vec![0xFE, 0xFF, 0x00, b'v'];
+        assert_eq!((&bytes).into_string_bom_aware(), "v");
+        assert_eq!(bytes.as_slice().into_string_bom_aware(), "v");
+        assert_eq!(bytes.into_string_bom_aware(), "v");
+    }
+
+    #[test]
+    fn odd_byte_at_the_end() {
+        assert_eq!(bytes_into_string_bom_aware(b"\xFF\xFEh\x00i"), "h\u{FFFD}");
+        assert_eq!(bytes_into_string_bom_aware(b"\xFE\xFF\x00"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn utf8_bom_is_utf8() {
+        assert_eq!(bytes_into_string_bom_aware(b"\xEF\xBB\xBFh\x00i\xFF"), "h\0i\u{FFFD}");
+        let bytes = b"\xEF\xBB\xBFvalid".to_vec();
+        let ptr = bytes.as_ptr();
+        let text = bytes.into_string_bom_aware();
+        assert_eq!(text, "valid");
+        assert_eq!(text.as_ptr(), ptr);
+        // without a BOM nothing changes, two NULs are not a BOM
+        for bytes in TEST_BYTES {
+            assert_eq!(bytes_into_string_bom_aware(bytes), String::from_utf8_lossy(bytes));
+        }
+        assert_eq!(bytes_into_string_bom_aware(b"h\x00i\x00"), "h\0i\0");
+    }
+}
@@ -0,0 +1,978 @@
+//! Conversions with extra steps applied on top of `into_string`.
+
+use alloc::string::String;
+
+use super::{Fallback,IntoString,bytes_into_string_mixed,local_to_cow,local_validate};
+#[cfg(feature = "std")]
+use super::hook::{self,LossyEvent,LossyHook};
+
+/// Builder for a conversion with extra steps.
+///
+/// Every step is off by default, so `Converter::new().convert(x)` is the
+/// same as `x.into_string()`. The steps are applied _after_ the lossy
+/// conversion, so they see `U+FFFD` rather than the bad sequence.
+#[derive(Clone,Debug,Default)]
+pub struct Converter {
+    fallback: Option<Fallback>,
+    mixed: bool,
+    trim_trailing_nuls: bool,
+    strip_bom: bool,
+    strip_verbatim: bool,
+    strip_ansi: bool,
+    sanitize: Option<Sanitize>,
+    keep_tabs: bool,
+    keep_newlines: bool,
+    trim_start: bool,
+    trim_end: bool,
+    normalize_newlines: bool,
+    lone_cr: bool,
+    max_bytes: Option<usize>,
+    ellipsis: bool,
+    #[cfg(feature = "segmentation")]
+    max_graphemes: Option<usize>,
+    #[cfg(feature = "segmentation")]
+    grapheme_boundaries: bool,
+    case: Option<Case>,
+    #[cfg(feature = "normalize")]
+    normalization: Option<Normalization>,
+    #[cfg(feature = "std")]
+    lossy_hook: Option<LossyHook>,
+}
+
+/// What [`Converter::sanitize`] does with a control character
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum Sanitize {
+    /// Replaced with `U+FFFD`
+    #[default]
+    Replace,
+    /// Written in caret notation, `ESC` is `^[`, `CR` is `^M`, `DEL` is `^?`
+    Caret,
+    /// Removed
+    Remove,
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+enum Case {
+    Lower,
+    Upper,
+}
+
+#[cfg(feature = "normalize")]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+enum Normalization {
+    Nfc,
+    Nfkc,
+}
+
+impl Converter {
+
+    /// A converter with every step off
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The encoding [`convert_bytes`](Converter::convert_bytes) decodes
+    /// with when the bytes are not valid utf8, instead of replacing the
+    /// bad sequences with `U+FFFD`.
+    ///
+    /// Valid utf8 is always taken as utf8. `None` (the default) is the
+    /// normal lossy conversion.
+    pub fn fallback(mut self, fallback: Option<Fallback>) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Only decode the bytes which are not utf8 with the
+    /// [`fallback`](Converter::fallback), keeping the valid utf8 around
+    /// them, see [`bytes_into_string_mixed`]. Without it one bad byte
+    /// makes the whole input decode as the fallback.
+    pub fn mixed(mut self, on: bool) -> Self {
+        self.mixed = on;
+        self
+    }
+
+    /// Drop the run of `\0` bytes at the end before
+    /// [`convert_bytes`](Converter::convert_bytes) decodes, for fixed size
+    /// buffers padded out with NULs.
+    ///
+    /// NULs before the last non NUL byte are kept (as `\0`, or whatever
+    /// [`sanitize`](Converter::sanitize) makes of them). To stop at the first
+    /// NUL instead see [`ffi::c_char_array_into_string`](crate::ffi::c_char_array_into_string).
+    pub fn trim_trailing_nuls(mut self, on: bool) -> Self {
+        self.trim_trailing_nuls = on;
+        self
+    }
+
+    /// Remove a byte order mark (`U+FEFF`) from the start of the output.
+    ///
+    /// Only the first one goes, a `U+FEFF` later in the text is kept. This
+    /// is meant for text from Windows tools which starts with the utf8 BOM
+    /// `EF BB BF`. Byte buffers and files can skip the `Converter` with
+    /// [`IntoStringNoBom`](crate::IntoStringNoBom) and
+    /// [`fs::read_to_string_lossy_no_bom`](crate::fs::read_to_string_lossy_no_bom).
+    pub fn strip_bom(mut self, on: bool) -> Self {
+        self.strip_bom = on;
+        self
+    }
+
+    /// Remove the Windows verbatim prefix from a path, `\\?\C:\dir` becomes
+    /// `C:\dir` and `\\?\UNC\server\share` becomes `\\server\share`.
+    ///
+    /// This is meant for the `PathBuf` from `fs::canonicalize`, but it only
+    /// looks at the text, so it is the same on every platform and for any
+    /// input which starts with one of these. Device paths (`\\.\COM1`),
+    /// verbatim paths without a drive letter and everything else are left
+    /// alone, see [`path::strip_verbatim_prefix`](crate::path::strip_verbatim_prefix)
+    /// for a single path.
+    pub fn strip_verbatim(mut self, on: bool) -> Self {
+        self.strip_verbatim = on;
+        self
+    }
+
+    /// Remove ANSI escape sequences, the colours and the like in the output
+    /// of a program which thought it was writing to a terminal.
+    ///
+    /// CSI sequences (`ESC [`, parameters, a final byte), OSC sequences
+    /// (`ESC ]` up to a `BEL` or `ESC \`) and any other `ESC` with the one
+    /// character after it are removed. A sequence cut off by the end of
+    /// the text is removed too, rather than leaving a stray `ESC`. This runs
+    /// before [`sanitize`](Converter::sanitize).
+    pub fn strip_ansi(mut self, on: bool) -> Self {
+        self.strip_ansi = on;
+        self
+    }
+
+    /// Get rid of the C0 control characters (`U+0000` to `U+001F`) and
+    /// `DEL`, for text going to a terminal or a single line of a log where
+    /// an escape sequence or a `\r` could do damage.
+    ///
+    /// `None` (the default) leaves them alone. Tabs and newlines are
+    /// controls too unless [`keep_tabs`](Converter::keep_tabs) or
+    /// [`keep_newlines`](Converter::keep_newlines) say otherwise.
+    pub fn sanitize(mut self, sanitize: Option<Sanitize>) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    /// Leave `\t` alone when sanitizing
+    pub fn keep_tabs(mut self, on: bool) -> Self {
+        self.keep_tabs = on;
+        self
+    }
+
+    /// Leave `\n` alone when sanitizing, along with a `\r` right before
+    /// one so `\r\n` line endings survive. Any other `\r` is still a control.
+    pub fn keep_newlines(mut self, on: bool) -> Self {
+        self.keep_newlines = on;
+        self
+    }
+
+    /// Fold `\r\n` into `\n`, for text from Windows tools.
+    ///
+    /// This is done in place on the converted `String`, so an owned input
+    /// which is handed over keeps its buffer and there is no second copy.
+    /// A `\r` which is not followed by `\n` (at the end, or before a
+    /// `U+FFFD`) is kept unless [`lone_cr`](Converter::lone_cr) is on.
+    pub fn normalize_newlines(mut self, on: bool) -> Self {
+        self.normalize_newlines = on;
+        self
+    }
+
+    /// With [`normalize_newlines`](Converter::normalize_newlines), a `\r` on
+    /// its own is a line ending too and becomes `\n`, so `\r\r\n` is `\n\n`
+    pub fn lone_cr(mut self, on: bool) -> Self {
+        self.lone_cr = on;
+        self
+    }
+
+    /// Remove whitespace from the start, as `str::trim_start` does.
+    ///
+    /// This happens after the lossy conversion, so a `U+FFFD` from a bad
+    /// byte is not whitespace. The buffer is kept, the rest of the text is
+    /// moved down in place.
+    pub fn trim_start(mut self, on: bool) -> Self {
+        self.trim_start = on;
+        self
+    }
+
+    /// Remove whitespace from the end, as `str::trim_end` does.
+    ///
+    /// The `String` is truncated in place, so an owned input which is
+    /// handed over keeps its buffer and nothing is copied.
+    pub fn trim_end(mut self, on: bool) -> Self {
+        self.trim_end = on;
+        self
+    }
+
+    /// Both [`trim_start`](Converter::trim_start) and [`trim_end`](Converter::trim_end)
+    pub fn trim(self, on: bool) -> Self {
+        self.trim_start(on).trim_end(on)
+    }
+
+    /// Normalize the output to (canonical) NFC.
+    ///
+    /// Replaces `nfkc`, only one normalization form can be used.
+    #[cfg(feature = "normalize")]
+    pub fn nfc(mut self, on: bool) -> Self {
+        self.set_normalization(Normalization::Nfc, on);
+        self
+    }
+
+    /// Normalize the output to (compatibility) NFKC.
+    ///
+    /// Replaces `nfc`, only one normalization form can be used.
+    #[cfg(feature = "normalize")]
+    pub fn nfkc(mut self, on: bool) -> Self {
+        self.set_normalization(Normalization::Nfkc, on);
+        self
+    }
+
+    /// Lowercase the output, with the full Unicode mapping of
+    /// `str::to_lowercase` rather than only ascii.
+    ///
+    /// The mapping is the default one, not a locale's, so Turkish `I` is
+    /// `i` (not dotless `ı`) and `İ` is `i` followed by a combining dot.
+    /// Replaces `uppercase`, only one can be used.
+    ///
+    /// Only all ascii text is folded in place. Anything else is folded
+    /// after the conversion into a new `String`, a second pass over the
+    /// text and a second allocation, since the mapping can change the
+    /// length.
+    pub fn lowercase(mut self, on: bool) -> Self {
+        self.set_case(Case::Lower, on);
+        self
+    }
+
+    /// Uppercase the output, with the full Unicode mapping of
+    /// `str::to_uppercase`, so `ß` becomes `SS` and the text can grow.
+    ///
+    /// Replaces `lowercase`, only one can be used. As with
+    /// [`lowercase`](Converter::lowercase) only all ascii text is folded in
+    /// place, anything else takes a second pass and a new `String`.
+    pub fn uppercase(mut self, on: bool) -> Self {
+        self.set_case(Case::Upper, on);
+        self
+    }
+
+    fn set_case(&mut self, case: Case, on: bool) {
+        if on {
+            self.case = Some(case);
+        } else if self.case == Some(case) {
+            self.case = None;
+        }
+    }
+
+    /// Cut the output down to at most `max_bytes` bytes, on a char boundary.
+    ///
+    /// This is the last step, so a `U+FFFD` from a bad sequence counts
+    /// towards the limit like any other char (three bytes). `None` (the
+    /// default) leaves the length alone.
+    pub fn max_bytes(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Cut the output down to at most `max_graphemes` extended grapheme
+    /// clusters, so an emoji sequence or a letter with its combining accents
+    /// is kept whole or dropped whole. With [`ellipsis`](Converter::ellipsis)
+    /// the `…` counts as one of them.
+    ///
+    /// Applied before [`max_bytes`](Converter::max_bytes) when both are on.
+    #[cfg(feature = "segmentation")]
+    pub fn max_graphemes(mut self, max_graphemes: Option<usize>) -> Self {
+        self.max_graphemes = max_graphemes;
+        self
+    }
+
+    /// Make [`max_bytes`](Converter::max_bytes) back off to the end of the
+    /// last whole grapheme cluster, instead of any char boundary.
+    #[cfg(feature = "segmentation")]
+    pub fn grapheme_boundaries(mut self, on: bool) -> Self {
+        self.grapheme_boundaries = on;
+        self
+    }
+
+    /// When [`max_bytes`](Converter::max_bytes) cuts something off, end
+    /// with `…` to show it. The `…` counts towards the limit, and is left
+    /// off when the limit is too small to hold it.
+    pub fn ellipsis(mut self, on: bool) -> Self {
+        self.ellipsis = on;
+        self
+    }
+
+    #[cfg(feature = "normalize")]
+    fn set_normalization(&mut self, form: Normalization, on: bool) {
+        if on {
+            self.normalization = Some(form);
+        } else if self.normalization == Some(form) {
+            self.normalization = None;
+        }
+    }
+
+    /// Calls `hook` for every bad sequence replaced while this converter
+    /// runs, on top of the global [`set_lossy_hook`](crate::hook::set_lossy_hook).
+    ///
+    /// See [`hook`](crate::hook) for which conversions report anything.
+    #[cfg(feature = "std")]
+    pub fn on_lossy(mut self, hook: impl Fn(&LossyEvent) + Send + Sync + 'static) -> Self {
+        self.lossy_hook = Some(LossyHook(std::sync::Arc::new(hook)));
+        self
+    }
+
+    /// Converts `value` and applies every step which is on
+    pub fn convert<T: IntoString>(&self, value: T) -> String {
+        let text = self.with_hook(|| value.into_string());
+        self.apply(text)
+    }
+
+    /// Converts raw `bytes`, using the [`fallback`](Converter::fallback)
+    /// encoding when they are not utf8, and applies every step which is on
+    pub fn convert_bytes(&self, mut bytes: &[u8]) -> String {
+        if self.trim_trailing_nuls {
+            let len = bytes.iter().rposition(|x| *x != 0).map_or(0, |x| x + 1);
+            bytes = &bytes[..len];
+        }
+        let text = match (local_validate(bytes), self.fallback) {
+            (Some(valid), _) => String::from(valid),
+            (None, Some(fallback)) if self.mixed => bytes_into_string_mixed(bytes, fallback),
+            (None, Some(fallback)) => fallback.decode(bytes),
+            (None, None) => self.with_hook(|| local_to_cow("[u8]", bytes).into_owned()),
+        };
+        self.apply(text)
+    }
+
+    // runs `f` with the `on_lossy` hook installed
+    fn with_hook<R>(&self, f: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "std")]
+        if let Some(lossy_hook) = &self.lossy_hook {
+            return hook::with_scoped(lossy_hook, f);
+        }
+        f()
+    }
+
+    fn apply(&self, mut text: String) -> String {
+        if self.strip_bom && text.starts_with('\u{FEFF}') {
+            // in place, the buffer is kept
+            text.drain(..'\u{FEFF}'.len_utf8());
+        }
+        if self.strip_verbatim {
+            strip_verbatim(&mut text);
+        }
+        if self.strip_ansi && text.contains('\x1b') {
+            text = strip_ansi(&text);
+        }
+        if let Some(mode) = self.sanitize {
+            text = self.sanitize_controls(text, mode);
+        }
+        if self.normalize_newlines && text.contains('\r') {
+            text = fold_newlines(text, self.lone_cr);
+        }
+        if self.trim_end {
+            let len = text.trim_end().len();
+            text.truncate(len);
+        }
+        if self.trim_start {
+            let start = text.len() - text.trim_start().len();
+            text.drain(..start);
+        }
+        // in place for ascii, otherwise the text is copied once more
+        match self.case {
+            Some(Case::Lower) if text.is_ascii() => text.make_ascii_lowercase(),
+            Some(Case::Upper) if text.is_ascii() => text.make_ascii_uppercase(),
+            Some(Case::Lower) => text = text.to_lowercase(),
+            Some(Case::Upper) => text = text.to_uppercase(),
+            None => {}
+        }
+        #[cfg(feature = "normalize")]
+        if let Some(form) = self.normalization {
+            text = normalize(text, form);
+        }
+        #[cfg(feature = "segmentation")]
+        if let Some(max) = self.max_graphemes {
+            truncate_graphemes(&mut text, max, self.ellipsis);
+        }
+        if let Some(max) = self.max_bytes {
+            #[cfg(feature = "segmentation")]
+            if self.grapheme_boundaries {
+                truncate_bytes_graphemes(&mut text, max, self.ellipsis);
+                return text;
+            }
+            truncate(&mut text, max, self.ellipsis);
+        }
+        text
+    }
+
+    // text without anything to change is returned as is
+    fn sanitize_controls(&self, text: String, mode: Sanitize) -> String {
+        let is_control = |c: char, next: Option<char>| match c {
+            '\t' => !self.keep_tabs,
+            '\n' => !self.keep_newlines,
+            '\r' => !(self.keep_newlines && next == Some('\n')),
+            _ => c.is_ascii_control(),
+        };
+        let mut chars = text.chars().peekable();
+        let mut any = false;
+        while let Some(c) = chars.next() {
+            if is_control(c, chars.peek().copied()) {
+                any = true;
+                break;
+            }
+        }
+        if !any {
+            return text;
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if !is_control(c, chars.peek().copied()) {
+                out.push(c);
+                continue;
+            }
+            match mode {
+                Sanitize::Replace => out.push(char::REPLACEMENT_CHARACTER),
+                Sanitize::Caret => {
+                    out.push('^');
+                    out.push(char::from(c as u8 ^ 0x40));
+                }
+                Sanitize::Remove => {}
+            }
+        }
+        out
+    }
+}
+
+/// Shorthands for the [`Converter`] steps, implemented for everything
+/// which implements `IntoString`.
+pub trait IntoStringExt: IntoString + Sized {
+
+    /// Converts and removes a leading byte order mark, see [`Converter::strip_bom`]
+    fn into_string_no_bom(self) -> String {
+        Converter::new().strip_bom(true).convert(self)
+    }
+
+    /// Converts and cuts the result down to at most `max_bytes` bytes,
+    /// see [`Converter::max_bytes`]
+    fn into_string_truncated(self, max_bytes: usize) -> String {
+        Converter::new().max_bytes(Some(max_bytes)).convert(self)
+    }
+
+    /// Same as [`into_string_truncated`](IntoStringExt::into_string_truncated),
+    /// ending with `…` when something was cut off, see [`Converter::ellipsis`]
+    fn into_string_truncated_with_ellipsis(self, max_bytes: usize) -> String {
+        Converter::new().max_bytes(Some(max_bytes)).ellipsis(true).convert(self)
+    }
+
+    /// Converts and cuts the result down to at most `max_graphemes`
+    /// grapheme clusters, see [`Converter::max_graphemes`]
+    #[cfg(feature = "segmentation")]
+    fn into_string_truncated_graphemes(self, max_graphemes: usize) -> String {
+        Converter::new().max_graphemes(Some(max_graphemes)).convert(self)
+    }
+
+    /// Converts and cuts the result down to at most `max_bytes` bytes,
+    /// ending on a grapheme cluster boundary, see [`Converter::grapheme_boundaries`]
+    #[cfg(feature = "segmentation")]
+    fn into_string_truncated_graphemes_bytes(self, max_bytes: usize) -> String {
+        Converter::new().max_bytes(Some(max_bytes)).grapheme_boundaries(true).convert(self)
+    }
+
+    /// Converts and removes ANSI escape sequences, see [`Converter::strip_ansi`]
+    fn into_string_no_ansi(self) -> String {
+        Converter::new().strip_ansi(true).convert(self)
+    }
+
+    /// Converts and replaces every control character (tabs and newlines
+    /// included) with `U+FFFD`, see [`Converter::sanitize`]
+    fn into_string_sanitized(self) -> String {
+        Converter::new().sanitize(Some(Sanitize::Replace)).convert(self)
+    }
+
+    /// Converts and normalizes to NFC, see [`Converter::nfc`]
+    #[cfg(feature = "normalize")]
+    fn into_string_nfc(self) -> String {
+        Converter::new().nfc(true).convert(self)
+    }
+
+    /// Converts and normalizes to NFKC, see [`Converter::nfkc`]
+    #[cfg(feature = "normalize")]
+    fn into_string_nfkc(self) -> String {
+        Converter::new().nfkc(true).convert(self)
+    }
+}
+
+impl<T: IntoString> IntoStringExt for T { }
+
+// cuts on the char boundary at or before `max`, the `…` only goes on
+// when there is room for it
+fn truncate(text: &mut String, max: usize, ellipsis: bool) {
+    if text.len() <= max {
+        return;
+    }
+    let room = '…'.len_utf8();
+    let mut end = if ellipsis && max >= room { max - room } else { max };
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    if ellipsis && max >= room {
+        text.push('…');
+    }
+}
+
+// the `…` takes the place of the last grapheme which would fit
+#[cfg(feature = "segmentation")]
+fn truncate_graphemes(text: &mut String, max: usize, ellipsis: bool) {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let start_of = |n: usize| text.grapheme_indices(true).nth(n).map(|(x, _)| x);
+    // no grapheme after the first `max`, nothing is cut off
+    let Some(mut end) = start_of(max) else {
+        return;
+    };
+    let ellipsis = ellipsis && max > 0;
+    if ellipsis {
+        end = start_of(max - 1).unwrap_or(end);
+    }
+    text.truncate(end);
+    if ellipsis {
+        text.push('…');
+    }
+}
+
+// the same as `truncate`, backing off to a grapheme boundary
+#[cfg(feature = "segmentation")]
+fn truncate_bytes_graphemes(text: &mut String, max: usize, ellipsis: bool) {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    if text.len() <= max {
+        return;
+    }
+    let room = '…'.len_utf8();
+    let limit = if ellipsis && max >= room { max - room } else { max };
+    let end = text.grapheme_indices(true)
+        .map(|(x, g)| x + g.len())
+        .take_while(|x| *x <= limit)
+        .last()
+        .unwrap_or(0);
+    text.truncate(end);
+    if ellipsis && max >= room {
+        text.push('…');
+    }
+}
+
+// `\r` and `\n` are ascii, so they can be moved about in the bytes
+fn fold_newlines(text: String, lone_cr: bool) -> String {
+    let mut bytes = text.into_bytes();
+    let mut write = 0;
+    let mut read = 0;
+    while read < bytes.len() {
+        let x = bytes[read];
+        read += 1;
+        if x == b'\r' && bytes.get(read) == Some(&b'\n') {
+            read += 1;
+            bytes[write] = b'\n';
+        } else if x == b'\r' && lone_cr {
+            bytes[write] = b'\n';
+        } else {
+            bytes[write] = x;
+        }
+        write += 1;
+    }
+    bytes.truncate(write);
+    // SAFETY: only the ascii `\r` and `\n` were replaced or removed,
+    // every other byte is in the same order, so this is still utf8
+    unsafe { String::from_utf8_unchecked(bytes) }
+}
+
+// `\\?\` before a drive letter is dropped and `\\?\UNC\` becomes `\\`,
+// in place
+pub(crate) fn strip_verbatim(text: &mut String) {
+    const VERBATIM: &str = r"\\?\";
+    const UNC: &str = r"\\?\UNC\";
+    if let Some(rest) = text.strip_prefix(UNC) {
+        if !rest.is_empty() {
+            text.replace_range(..UNC.len(), r"\\");
+        }
+    } else if let Some(rest) = text.strip_prefix(VERBATIM) {
+        let drive = rest.as_bytes();
+        if drive.len() >= 2 && drive[0].is_ascii_alphabetic() && drive[1] == b':' {
+            text.drain(..VERBATIM.len());
+        }
+    }
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // parameters and intermediates, up to the final byte, anything
+            // else ends a malformed sequence and is kept
+            Some('[') => {
+                while let Some(&c) = chars.peek() {
+                    if !('\x20'..='\x7e').contains(&c) {
+                        break;
+                    }
+                    chars.next();
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+// text which is already normalized is returned as is
+#[cfg(feature = "normalize")]
+fn normalize(text: String, form: Normalization) -> String {
+    use unicode_normalization::{UnicodeNormalization,is_nfc,is_nfkc};
+    match form {
+        Normalization::Nfc if !is_nfc(&text) => text.nfc().collect(),
+        Normalization::Nfkc if !is_nfkc(&text) => text.nfkc().collect(),
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn every_step_off_is_into_string() {
+        for bytes in TEST_BYTES {
+            let expected = String::from_utf8_lossy(bytes);
+            assert_eq!(Converter::new().convert_bytes(bytes), expected);
+            assert_eq!(Converter::new().convert(expected.as_ref()), expected);
+        }
+        assert_eq!(Converter::new().convert("e\u{301}"), "e\u{301}");
+    }
+
+    #[test]
+    fn strip_bom_only_the_first() {
+        let strip = Converter::new().strip_bom(true);
+        assert_eq!(strip.convert_bytes(b"\xEF\xBB\xBFtext"), "text");
+        assert_eq!(strip.convert_bytes(b"\xEF\xBB\xBF\xEF\xBB\xBFtext"), "\u{FEFF}text");
+        assert_eq!(strip.convert_bytes(b"te\xEF\xBB\xBFxt"), "te\u{FEFF}xt");
+        assert_eq!(strip.convert_bytes(b"\xEF\xBB\xBF"), "");
+        assert_eq!("\u{FEFF}str".into_string_no_bom(), "str");
+        // off by default
+        assert_eq!(Converter::new().convert_bytes(b"\xEF\xBB\xBFtext"), "\u{FEFF}text");
+        assert_eq!("\u{FEFF}str".into_string(), "\u{FEFF}str");
+    }
+
+    #[test]
+    fn strip_verbatim_paths() {
+        let strip = Converter::new().strip_verbatim(true);
+        assert_eq!(strip.convert(r"\\?\C:\Users\me"), r"C:\Users\me");
+        assert_eq!(strip.convert(r"\\?\UNC\server\share\dir"), r"\\server\share\dir");
+        for text in [r"\\.\PhysicalDrive0", r"\\?\GLOBALROOT\Device", r"\\server\share", r"C:\dir", "dir/file", ""] {
+            assert_eq!(strip.convert(text), text);
+        }
+        // only at the start
+        assert_eq!(strip.convert(r"x\\?\C:\"), r"x\\?\C:\");
+        // off by default
+        assert_eq!(Converter::new().convert(r"\\?\C:\"), r"\\?\C:\");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strip_verbatim_os_strings() {
+        let strip = Converter::new().strip_verbatim(true);
+        let path = std::path::PathBuf::from(r"\\?\D:\data");
+        assert_eq!(strip.convert(path.as_os_str()), r"D:\data");
+        assert_eq!(strip.convert(path.into_os_string()), r"D:\data");
+        // after the bom is gone
+        let both = Converter::new().strip_bom(true).strip_verbatim(true);
+        assert_eq!(both.convert_bytes("\u{FEFF}\\\\?\\E:\\".as_bytes()), r"E:\");
+    }
+
+    #[test]
+    fn fallback_only_when_not_utf8() {
+        let latin1 = Converter::new().fallback(Some(Fallback::Latin1));
+        assert_eq!(latin1.convert_bytes("caf\u{e9}".as_bytes()), "caf\u{e9}");
+        assert_eq!(latin1.convert_bytes(b"caf\xE9"), "caf\u{e9}");
+        // without `mixed` one bad byte decodes everything as the fallback
+        assert_eq!(latin1.convert_bytes(b"\xC3\xA9 \xE9"), "\u{c3}\u{a9} \u{e9}");
+        assert_eq!(Converter::new().convert_bytes(b"caf\xE9"), "caf\u{FFFD}");
+    }
+
+    #[test]
+    fn cp1252_fallback() {
+        let cp1252 = Converter::new().fallback(Some(Fallback::Cp1252));
+        assert_eq!(cp1252.convert_bytes(b"\x93q\x94 \x80"), "\u{201C}q\u{201D} \u{20AC}");
+        assert_eq!(cp1252.convert_bytes("\u{201C}q\u{201D}".as_bytes()), "\u{201C}q\u{201D}");
+    }
+
+    #[test]
+    fn mixed_keeps_the_utf8() {
+        let mixed = Converter::new().fallback(Some(Fallback::Latin1)).mixed(true);
+        assert_eq!(mixed.convert_bytes(b"\xC3\xA9 \xE9"), "\u{e9} \u{e9}");
+        // no fallback, nothing to mix in
+        assert_eq!(Converter::new().mixed(true).convert_bytes(b"\xC3\xA9 \xE9"), "\u{e9} \u{FFFD}");
+    }
+
+    #[test]
+    fn sanitize_each_mode() {
+        let text = "a\x1b[1m\tb\r\nc\x7f";
+        let sanitize = |mode| Converter::new().sanitize(Some(mode));
+        assert_eq!(sanitize(Sanitize::Replace).convert(text), "a\u{FFFD}[1m\u{FFFD}b\u{FFFD}\u{FFFD}c\u{FFFD}");
+        assert_eq!(sanitize(Sanitize::Caret).convert(text), "a^[[1m^Ib^M^Jc^?");
+        assert_eq!(sanitize(Sanitize::Remove).convert(text), "a[1mbc");
+        assert_eq!(text.into_string_sanitized(), sanitize(Sanitize::Replace).convert(text));
+        // with tabs and newlines allowed
+        let keep = |mode| sanitize(mode).keep_tabs(true).keep_newlines(true);
+        assert_eq!(keep(Sanitize::Replace).convert(text), "a\u{FFFD}[1m\tb\r\nc\u{FFFD}");
+        assert_eq!(keep(Sanitize::Caret).convert(text), "a^[[1m\tb\r\nc^?");
+        assert_eq!(keep(Sanitize::Remove).convert(text), "a[1m\tb\r\nc");
+        // a `\r` which does not end a line is still a control
+        assert_eq!(keep(Sanitize::Caret).convert("a\rb\r"), "a^Mb^M");
+    }
+
+    #[test]
+    fn sanitize_keeps_clean_text() {
+        let text = String::from("clean text \u{e9}");
+        let ptr = text.as_ptr();
+        let text = Converter::new().sanitize(Some(Sanitize::Remove)).convert(text);
+        assert_eq!(text.as_ptr(), ptr);
+        assert_eq!(Converter::new().convert("a\x1bb"), "a\x1bb");
+    }
+
+    #[test]
+    fn strip_ansi_sequences() {
+        assert_eq!("\x1b[31mred\x1b[0m".into_string_no_ansi(), "red");
+        assert_eq!("\x1b[38;5;208morange\x1b[m".into_string_no_ansi(), "orange");
+        assert_eq!("\x1b]0;window title\x07text".into_string_no_ansi(), "text");
+        assert_eq!("\x1b]0;window title\x1b\\text".into_string_no_ansi(), "text");
+        // a bare ESC takes one character with it
+        assert_eq!("a\x1b7b\x1b8c".into_string_no_ansi(), "abc");
+        // cut off at the end
+        assert_eq!("text\x1b[38;5".into_string_no_ansi(), "text");
+        assert_eq!("text\x1b".into_string_no_ansi(), "text");
+        assert_eq!("text\x1b]0;tit".into_string_no_ansi(), "text");
+        // a CSI broken by a control ends there, the control is kept
+        assert_eq!("a\x1b[1\nb".into_string_no_ansi(), "a\nb");
+    }
+
+    #[test]
+    fn strip_ansi_then_sanitize() {
+        let converter = Converter::new().strip_ansi(true).sanitize(Some(Sanitize::Caret));
+        assert_eq!(converter.convert_bytes(b"\x1b[1mbold\x1b[0m\r\xFF"), "bold^M\u{FFFD}");
+    }
+
+    #[test]
+    fn trim_owned_in_place() {
+        let text = String::from("value\n");
+        let ptr = text.as_ptr();
+        let text = Converter::new().trim_end(true).convert(text);
+        assert_eq!(text, "value");
+        assert_eq!(text.as_ptr(), ptr);
+        let text = String::from(" \u{3000}value\u{2003} ");
+        let ptr = text.as_ptr();
+        let text = Converter::new().trim(true).convert(text);
+        assert_eq!(text, "value");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn trim_start_borrowed() {
+        let name = std::ffi::OsStr::new("   padded ");
+        assert_eq!(Converter::new().trim_start(true).convert(name), "padded ");
+        assert_eq!(Converter::new().trim_end(true).convert(name), "   padded");
+    }
+
+    // the `U+FFFD` is not whitespace, the space before it stays
+    #[test]
+    fn trim_after_replacement() {
+        let trim = Converter::new().trim(true);
+        assert_eq!(trim.convert_bytes(b" text \xFF"), "text \u{FFFD}");
+        assert_eq!(trim.convert_bytes(b"\xC3 text \n"), "\u{FFFD} text");
+        assert_eq!(trim.convert_bytes(b" \t\n"), "");
+    }
+
+    #[test]
+    fn trim_trailing_nuls_only() {
+        let trim = Converter::new().trim_trailing_nuls(true);
+        assert_eq!(trim.convert_bytes(b"name\0\0\0\0"), "name");
+        assert_eq!(trim.convert_bytes(b"a\0b\0\0c\0\0"), "a\0b\0\0c");
+        assert_eq!(trim.convert_bytes(&[0; 16]), "");
+        assert_eq!(trim.convert_bytes(b"no nuls"), "no nuls");
+        assert_eq!(trim.convert_bytes(b"\xFF\0"), "\u{FFFD}");
+        // the kept NULs go through the other steps
+        let caret = trim.sanitize(Some(Sanitize::Caret));
+        assert_eq!(caret.convert_bytes(b"a\0b\0\0"), "a^@b");
+        assert_eq!(Converter::new().convert_bytes(b"a\0\0"), "a\0\0");
+    }
+
+    #[test]
+    fn normalize_newlines_edges() {
+        let crlf = Converter::new().normalize_newlines(true);
+        let lone = crlf.clone().lone_cr(true);
+        assert_eq!(crlf.convert("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(crlf.convert("end\r"), "end\r");
+        assert_eq!(lone.convert("end\r"), "end\n");
+        assert_eq!(crlf.convert_bytes(b"a\r\xFFb"), "a\r\u{FFFD}b");
+        assert_eq!(lone.convert_bytes(b"a\r\xFFb"), "a\n\u{FFFD}b");
+        assert_eq!(crlf.convert("a\r\r\nb"), "a\r\nb");
+        assert_eq!(lone.convert("a\r\r\nb"), "a\n\nb");
+        // lone_cr alone does nothing
+        assert_eq!(Converter::new().lone_cr(true).convert("a\rb"), "a\rb");
+    }
+
+    #[test]
+    fn normalize_newlines_in_place() {
+        let crlf = Converter::new().normalize_newlines(true);
+        for input in ["nothing to do\n", "windows\r\nline\r\n"] {
+            let text = String::from(input);
+            let ptr = text.as_ptr();
+            let text = crlf.convert(text);
+            assert_eq!(text, input.replace("\r\n", "\n"));
+            assert_eq!(text.as_ptr(), ptr);
+        }
+    }
+
+    #[test]
+    fn truncate_on_char_boundaries() {
+        // `é` is bytes 1 and 2
+        assert_eq!("h\u{e9}llo".into_string_truncated(2), "h");
+        assert_eq!("h\u{e9}llo".into_string_truncated(3), "h\u{e9}");
+        assert_eq!("hello".into_string_truncated(5), "hello");
+        assert_eq!("hello".into_string_truncated(0), "");
+        assert_eq!("hello world".into_string_truncated_with_ellipsis(8), "hello\u{2026}");
+        assert_eq!("hello".into_string_truncated_with_ellipsis(5), "hello");
+        // no room for the `…`, which is 3 bytes
+        assert_eq!("hello".into_string_truncated_with_ellipsis(2), "he");
+        assert_eq!("h\u{e9}llo".into_string_truncated_with_ellipsis(5), "h\u{2026}");
+    }
+
+    // the `U+FFFD` takes 3 bytes like any other char
+    #[test]
+    fn truncate_after_replacement() {
+        let max = |n| Converter::new().max_bytes(Some(n));
+        assert_eq!(max(5).convert_bytes(b"ab\xFF"), "ab\u{FFFD}");
+        assert_eq!(max(4).convert_bytes(b"ab\xFF"), "ab");
+        assert_eq!(max(4).ellipsis(true).convert_bytes(b"ab\xFF"), "a\u{2026}");
+        assert_eq!(max(5).ellipsis(true).convert_bytes(b"ab\xFF"), "ab\u{FFFD}");
+    }
+
+    #[test]
+    fn case_folding() {
+        let lower = Converter::new().lowercase(true);
+        let upper = Converter::new().uppercase(true);
+        assert_eq!(lower.convert("MiXeD Ascii"), "mixed ascii");
+        assert_eq!(upper.convert("MiXeD Ascii"), "MIXED ASCII");
+        assert_eq!(upper.convert("stra\u{df}e"), "STRASSE");
+        assert_eq!(lower.convert("STRASSE"), "strasse");
+        // the default mapping, not the Turkish one
+        assert_eq!(lower.convert("I"), "i");
+        assert_eq!(lower.convert("\u{130}"), "i\u{307}");
+        assert_eq!(upper.convert("i\u{131}"), "II");
+        // replaced first, `U+FFFD` has no case
+        assert_eq!(upper.convert_bytes(b"ab\xFFc"), "AB\u{FFFD}C");
+        // the last one set wins, turning off the other leaves it on
+        assert_eq!(lower.clone().uppercase(true).convert("aB"), "AB");
+        assert_eq!(lower.clone().uppercase(false).convert("aB"), "ab");
+    }
+
+    #[test]
+    fn ascii_case_in_place() {
+        let text = String::from("ASCII ONLY");
+        let ptr = text.as_ptr();
+        let text = Converter::new().lowercase(true).convert(text);
+        assert_eq!(text, "ascii only");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn graphemes_are_kept_whole() {
+        // man, woman and girl joined with ZWJ, one grapheme of 18 bytes
+        let text = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+        assert_eq!(text.into_string_truncated_graphemes(2), "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}");
+        assert_eq!(text.into_string_truncated_graphemes(3), text);
+        assert_eq!(text.into_string_truncated_graphemes_bytes(18), "a");
+        assert_eq!(text.into_string_truncated_graphemes_bytes(19), "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}");
+        // `e` with two combining accents
+        let accents = "e\u{301}\u{302}x";
+        assert_eq!(accents.into_string_truncated_graphemes(1), "e\u{301}\u{302}");
+        assert_eq!(accents.into_string_truncated_graphemes_bytes(4), "");
+        assert_eq!(accents.into_string_truncated(4), "e\u{301}");
+    }
+
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn graphemes_with_ellipsis() {
+        let text = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+        let graphemes = |n| Converter::new().max_graphemes(Some(n)).ellipsis(true);
+        assert_eq!(graphemes(2).convert(text), "a\u{2026}");
+        assert_eq!(graphemes(3).convert(text), text);
+        assert_eq!(graphemes(0).convert(text), "");
+        // 20 bytes, the `…` leaves 16 for the text
+        let bytes = |n| Converter::new().max_bytes(Some(n)).grapheme_boundaries(true).ellipsis(true);
+        assert_eq!(bytes(19).convert(text), "a\u{2026}");
+        assert_eq!(bytes(20).convert(text), text);
+    }
+
+    // on ascii every char is a grapheme
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn graphemes_same_as_chars_on_ascii() {
+        let text = "plain ascii text";
+        for n in 0..=text.len() + 1 {
+            assert_eq!(text.into_string_truncated_graphemes(n), text.into_string_truncated(n));
+            assert_eq!(text.into_string_truncated_graphemes_bytes(n), text.into_string_truncated(n));
+            let ellipsis = |c: Converter| c.ellipsis(true).convert(text);
+            assert_eq!(
+                ellipsis(Converter::new().max_bytes(Some(n)).grapheme_boundaries(true)),
+                ellipsis(Converter::new().max_bytes(Some(n))),
+            );
+        }
+    }
+
+    #[cfg(feature = "normalize")]
+    #[test]
+    fn nfc_composes_decomposed_names() {
+        use std::ffi::OsStr;
+
+        let name = OsStr::new("cafe\u{301}");
+        assert_eq!(name.into_string(), "cafe\u{301}");
+        assert_eq!(name.into_string_nfc(), "caf\u{e9}");
+        assert_eq!(Converter::new().nfc(true).convert(name), "caf\u{e9}");
+        assert_eq!(Converter::new().convert(name), "cafe\u{301}");
+    }
+
+    #[cfg(feature = "normalize")]
+    #[test]
+    fn nfkc_and_nfc_replace_each_other() {
+        let text = "\u{FB01}le e\u{301}";
+        assert_eq!(text.into_string_nfc(), "\u{FB01}le \u{e9}");
+        assert_eq!(text.into_string_nfkc(), "file \u{e9}");
+        assert_eq!(Converter::new().nfc(true).nfkc(true).convert(text), "file \u{e9}");
+        assert_eq!(Converter::new().nfkc(true).nfc(true).convert(text), "\u{FB01}le \u{e9}");
+        // turning off a form which is not the one set leaves it alone
+        assert_eq!(Converter::new().nfkc(true).nfc(false).convert(text), "file \u{e9}");
+        assert_eq!(Converter::new().nfkc(true).nfkc(false).convert(text), text);
+    }
+
+    #[cfg(feature = "normalize")]
+    #[test]
+    fn normalized_after_replacement() {
+        let bytes = b"e\xCC\x81\xFF";
+        assert_eq!(Converter::new().nfc(true).convert_bytes(bytes), "\u{e9}\u{FFFD}");
+    }
+}
@@ -0,0 +1,159 @@
+//! Decoding input which arrives in pieces.
+
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+
+use super::{count_conversion,local_validate,lossy};
+
+/// Converts bytes which arrive in pieces (reads from a pipe, chunks of a
+/// body) as they arrive, instead of collecting them first.
+///
+/// Joining everything the decoder produced gives the same text as a single
+/// `String::from_utf8_lossy` over all the pieces joined together. A
+/// sequence split between two pieces is held back (at most 3 bytes) until
+/// the next piece says whether it is valid. `U+FFFD` is only emitted once a
+/// sequence is definitely invalid, or by [`finish`](LossyDecoder::finish)
+/// when the input ends part way through one.
+#[derive(Clone,Debug,Default)]
+pub struct LossyDecoder {
+    pending: Vec<u8>,
+    chunk: String,
+}
+
+impl LossyDecoder {
+
+    /// A decoder with nothing held back
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes `bytes`, returning the text they completed.
+    ///
+    /// The returned text is only borrowed until the next call, use
+    /// [`push_to`](LossyDecoder::push_to) to collect into a `String` of
+    /// your own.
+    pub fn push(&mut self, bytes: &[u8]) -> &str {
+        let mut chunk = core::mem::take(&mut self.chunk);
+        chunk.clear();
+        self.push_to(bytes, &mut chunk);
+        self.chunk = chunk;
+        &self.chunk
+    }
+
+    /// Decodes `bytes` onto the end of `out`.
+    pub fn push_to(&mut self, bytes: &[u8], out: &mut String) {
+        if self.pending.is_empty() {
+            push_lossy(out, &mut self.pending, bytes);
+        } else {
+            // only copies when a sequence straddles two pieces
+            let mut joined = core::mem::take(&mut self.pending);
+            joined.extend_from_slice(bytes);
+            push_lossy(out, &mut self.pending, &joined);
+        }
+    }
+
+    /// Ends the input, returning the last of the text.
+    ///
+    /// This is empty, unless the input ended part way through a sequence,
+    /// which becomes a single `U+FFFD`.
+    pub fn finish(self) -> String {
+        let mut out = String::new();
+        self.finish_to(&mut out);
+        out
+    }
+
+    /// Ends the input, see [`finish`](LossyDecoder::finish), onto the end of `out`.
+    pub fn finish_to(self, out: &mut String) {
+        count_conversion();
+        if !self.pending.is_empty() {
+            lossy("LossyDecoder", &self.pending, 1);
+            out.push('\u{FFFD}');
+        }
+    }
+}
+
+// converts `bytes` onto the end of `text`, a trailing sequence which
+// could still be completed by more input is moved into `pending`
+fn push_lossy(text: &mut String, pending: &mut Vec<u8>, bytes: &[u8]) {
+    if let Some(valid) = local_validate(bytes) {
+        text.push_str(valid);
+        return;
+    }
+    let mut replaced = 0;
+    let mut chunks = bytes.utf8_chunks().peekable();
+    while let Some(chunk) = chunks.next() {
+        text.push_str(chunk.valid());
+        let invalid = chunk.invalid();
+        if invalid.is_empty() {
+            continue;
+        }
+        let incomplete = chunks.peek().is_none() && matches!(core::str::from_utf8(invalid), Err(e) if e.error_len().is_none());
+        if incomplete {
+            pending.extend_from_slice(invalid);
+        } else {
+            replaced += 1;
+            text.push('\u{FFFD}');
+        }
+    }
+    if replaced > 0 {
+        lossy("LossyDecoder", &bytes[..bytes.len() - pending.len()], replaced);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::ToOwned;
+
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn one_byte_at_a_time() {
+        let mut decoder = LossyDecoder::new();
+        for b in &"\u{1F600}".as_bytes()[..3] {
+            assert_eq!(decoder.push(&[*b]), "");
+        }
+        assert_eq!(decoder.push(&[0x80]), "\u{1F600}");
+        assert_eq!(decoder.finish(), "");
+    }
+
+    #[test]
+    fn invalid_byte_after_a_split() {
+        let mut decoder = LossyDecoder::new();
+        assert_eq!(decoder.push(b"a\xF0\x9F"), "a");
+        assert_eq!(decoder.push(b"\xFFb"), "\u{FFFD}\u{FFFD}b");
+        assert_eq!(decoder.push(b"\xFF"), "\u{FFFD}");
+        assert_eq!(decoder.finish(), "");
+    }
+
+    #[test]
+    fn finish_part_way_through() {
+        let mut decoder = LossyDecoder::new();
+        assert_eq!(decoder.push(b"ab\xE2\x82"), "ab");
+        assert_eq!(decoder.finish(), "\u{FFFD}");
+    }
+
+    // every split into two pieces, and one byte at a time
+    #[test]
+    fn same_as_from_utf8_lossy() {
+        for bytes in TEST_BYTES {
+            let expected = String::from_utf8_lossy(bytes);
+            for at in 0..=bytes.len() {
+                let mut decoder = LossyDecoder::new();
+                let mut text = decoder.push(&bytes[..at]).to_owned();
+                decoder.push_to(&bytes[at..], &mut text);
+                decoder.finish_to(&mut text);
+                assert_eq!(text, expected, "{bytes:02X?} split at {at}");
+            }
+            let mut decoder = LossyDecoder::new();
+            let mut text = String::new();
+            for b in bytes.iter() {
+                decoder.push_to(&[*b], &mut text);
+            }
+            text.push_str(&decoder.finish());
+            assert_eq!(text, expected);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Types which only have a `Display`.
+//!
+//! There can not be an `impl<T: Display> IntoString for T`. `String`,
+//! `&str`, `Cow<str>` and most of the other types here are `Display` as
+//! well, so it would overlap with their own impls (which keep the buffer
+//! rather than formatting it again), and with the `&T` impl every
+//! reference gets. Coherence rejects it even for types which are not
+//! `Display` today, since their crate could add it later. Going through
+//! `Display` has to be asked for instead, with [`display_to_string`] or
+//! [`ViaDisplay`].
+
+use alloc::string::String;
+use core::fmt::{self,Write};
+
+use super::{IntoString,IntoStringBase,sealed::Sealed};
+
+/// Formats `value` with its `Display` impl.
+///
+/// Unlike `ToString` this does not panic when the impl returns an error
+/// (a `Display` is only allowed to fail when the output does), whatever
+/// was written before the error is returned. `Display` has no way to say
+/// how long the output is going to be, so the buffer grows as it is
+/// written.
+pub fn display_to_string<T: fmt::Display + ?Sized>(value: &T) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "{}", value);
+    out
+}
+
+/// Converts any `Display` type through [`display_to_string`], for APIs
+/// which take an `IntoString`, `ViaDisplay(Ipv4Addr::LOCALHOST)` is
+/// `127.0.0.1`.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct ViaDisplay<T>(pub T);
+
+impl<T: fmt::Display> IntoString for ViaDisplay<T> {
+    fn into_string(self) -> String {
+        display_to_string(&self.0)
+    }
+}
+impl<T: fmt::Display> Sealed for ViaDisplay<T> {}
+impl<T: fmt::Display> IntoStringBase for ViaDisplay<T> {
+    fn ref_into_string(&self) -> String {
+        display_to_string(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::Ipv4Addr;
+
+    use super::*;
+
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl fmt::Display for Point {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "({}, {})", self.x, self.y)
+        }
+    }
+
+    // writes a bit, then fails
+    struct Fails;
+
+    impl fmt::Display for Fails {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("partial")?;
+            Err(fmt::Error)
+        }
+    }
+
+    fn takes_into_string(value: impl IntoString) -> String {
+        value.into_string()
+    }
+
+    #[test]
+    fn custom_display() {
+        let point = Point { x: 1, y: -2 };
+        assert_eq!(display_to_string(&point), "(1, -2)");
+        assert_eq!((&ViaDisplay(&point)).into_string(), "(1, -2)");
+        assert_eq!(takes_into_string(ViaDisplay(point)), "(1, -2)");
+    }
+
+    #[test]
+    fn std_display() {
+        assert_eq!(display_to_string(&Ipv4Addr::LOCALHOST), "127.0.0.1");
+        assert_eq!(takes_into_string(ViaDisplay(Ipv4Addr::new(10, 0, 0, 1))), "10.0.0.1");
+        assert_eq!(display_to_string("str"), "str");
+        let mut out = String::from("addr ");
+        ViaDisplay(Ipv4Addr::BROADCAST).push_into(&mut out);
+        assert_eq!(out, "addr 255.255.255.255");
+    }
+
+    #[test]
+    fn failing_display_keeps_what_was_written() {
+        assert_eq!(display_to_string(&Fails), "partial");
+        assert_eq!(ViaDisplay(Fails).into_string(), "partial");
+    }
+}
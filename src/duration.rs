@@ -0,0 +1,145 @@
+//! `Duration` written out for a person or for other programs to read.
+
+use alloc::string::String;
+use core::{fmt::Write,time::Duration};
+
+/// How [`duration_to_string`] writes a `Duration`
+#[non_exhaustive]
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum DurationStyle {
+    /// Days, hours, minutes and seconds for a person to read,
+    /// `1d 2h 3m 4.5s`.
+    ///
+    /// Zero parts are left out, two hours is `2h`, and the fraction of a
+    /// second is written without trailing zeros. Durations shorter than a
+    /// second use the largest of `ms`, `µs` and `ns` which is at least
+    /// one, `1.5ms` or `250ns`. The zero duration is `0s`.
+    #[default]
+    Human,
+    /// An ISO 8601 duration with only hours, minutes and seconds,
+    /// `PT1H2M3.5S`.
+    ///
+    /// Days, months and years are left out since their length depends on
+    /// the calendar, two days is `PT48H`. Zero parts are left out as well
+    /// (one hour is `PT1H`), except for the zero duration which is `PT0S`.
+    /// The fraction of a second is only written when there is one, without
+    /// trailing zeros, `PT0.25S`.
+    Iso8601,
+}
+
+/// Writes `d` in `style`
+pub fn duration_to_string(d: Duration, style: DurationStyle) -> String {
+    match style {
+        DurationStyle::Human => human(d),
+        DurationStyle::Iso8601 => iso8601(d),
+    }
+}
+
+fn human(d: Duration) -> String {
+    let secs = d.as_secs();
+    let nanos = d.subsec_nanos();
+    let mut out = String::new();
+    if secs == 0 {
+        match nanos {
+            0 => out.push_str("0s"),
+            1_000_000.. => write_fraction(&mut out, u64::from(nanos / 1_000_000), nanos % 1_000_000, 6, "ms"),
+            1_000.. => write_fraction(&mut out, u64::from(nanos / 1_000), nanos % 1_000, 3, "\u{b5}s"),
+            _ => write_fraction(&mut out, u64::from(nanos), 0, 0, "ns"),
+        }
+        return out;
+    }
+    let parts = [(secs / 86400, "d"), (secs / 3600 % 24, "h"), (secs / 60 % 60, "m")];
+    for (n, unit) in parts {
+        if n != 0 {
+            let _ = write!(out, "{}{} ", n, unit);
+        }
+    }
+    let seconds = secs % 60;
+    if seconds != 0 || nanos != 0 {
+        write_fraction(&mut out, seconds, nanos, 9, "s");
+    } else {
+        out.pop();
+    }
+    out
+}
+
+// `whole`, then `fraction` as `digits` decimal places without trailing
+// zeros (nothing when it is zero), then `unit`
+fn write_fraction(out: &mut String, whole: u64, fraction: u32, digits: usize, unit: &str) {
+    let _ = write!(out, "{}", whole);
+    if fraction != 0 {
+        let _ = write!(out, ".{:0width$}", fraction, width = digits);
+        let trimmed = out.trim_end_matches('0').len();
+        out.truncate(trimmed);
+    }
+    out.push_str(unit);
+}
+
+fn iso8601(d: Duration) -> String {
+    let secs = d.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let nanos = d.subsec_nanos();
+    let mut out = String::from("PT");
+    if hours != 0 {
+        let _ = write!(out, "{}H", hours);
+    }
+    if minutes != 0 {
+        let _ = write!(out, "{}M", minutes);
+    }
+    if seconds != 0 || nanos != 0 || out.len() == 2 {
+        write_fraction(&mut out, seconds, nanos, 9, "S");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iso(d: Duration) -> String {
+        duration_to_string(d, DurationStyle::Iso8601)
+    }
+
+    fn human(d: Duration) -> String {
+        duration_to_string(d, DurationStyle::Human)
+    }
+
+    #[test]
+    fn iso8601_zero_and_sub_second() {
+        assert_eq!(iso(Duration::ZERO), "PT0S");
+        assert_eq!(iso(Duration::from_millis(250)), "PT0.25S");
+        assert_eq!(iso(Duration::from_nanos(1)), "PT0.000000001S");
+        assert_eq!(iso(Duration::from_micros(1_500)), "PT0.0015S");
+    }
+
+    #[test]
+    fn iso8601_whole_parts() {
+        assert_eq!(iso(Duration::from_secs(3600)), "PT1H");
+        assert_eq!(iso(Duration::from_secs(60)), "PT1M");
+        assert_eq!(iso(Duration::from_secs(3600 + 120)), "PT1H2M");
+        assert_eq!(iso(Duration::from_secs(3600 + 2)), "PT1H2S");
+        assert_eq!(iso(Duration::new(3723, 500_000_000)), "PT1H2M3.5S");
+        // days are hours
+        assert_eq!(iso(Duration::from_secs(2 * 86400)), "PT48H");
+        assert_eq!(iso(Duration::from_secs(3 * 86400 + 3600 + 1)), "PT73H1S");
+        assert_eq!(iso(Duration::MAX), "PT5124095576030431H15.999999999S");
+    }
+
+    #[test]
+    fn human_parts() {
+        assert_eq!(human(Duration::ZERO), "0s");
+        assert_eq!(human(Duration::from_secs(2 * 3600)), "2h");
+        assert_eq!(human(Duration::new(86400 + 2 * 3600 + 3 * 60 + 4, 500_000_000)), "1d 2h 3m 4.5s");
+        assert_eq!(human(Duration::from_secs(86400 + 5)), "1d 5s");
+        assert_eq!(human(Duration::new(1, 1)), "1.000000001s");
+        assert_eq!(DurationStyle::default(), DurationStyle::Human);
+    }
+
+    #[test]
+    fn human_below_a_second() {
+        assert_eq!(human(Duration::from_micros(1_500)), "1.5ms");
+        assert_eq!(human(Duration::from_millis(999)), "999ms");
+        assert_eq!(human(Duration::from_nanos(1_001)), "1.001\u{b5}s");
+        assert_eq!(human(Duration::from_nanos(250)), "250ns");
+    }
+}
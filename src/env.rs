@@ -0,0 +1,239 @@
+//! Lossy versions of the path returning functions in
+//! [`std::env`](https://doc.rust-lang.org/std/env/index.html).
+//!
+//! Errors from the standard library are passed along untouched, only
+//! the successful `PathBuf` is converted.
+//!
+//! [`env_block_into_strings`] reads a whole environment block, as given to
+//! `CreateProcess` and the like.
+
+use std::{
+    env::{self,VarError},
+    ffi::OsString,
+    io,
+};
+
+use super::{IntoString,IntoStringBase,local_to_cow,local_wide_to_str,os::path_buf_to_str,sealed::Sealed};
+
+/// Returns the current working directory as a `String`.
+///
+/// See [`std::env::current_dir`](https://doc.rust-lang.org/std/env/fn.current_dir.html)
+pub fn current_dir_string() -> io::Result<String> {
+    env::current_dir().map(path_buf_to_str)
+}
+
+/// Returns the full path of the current executable as a `String`.
+///
+/// See [`std::env::current_exe`](https://doc.rust-lang.org/std/env/fn.current_exe.html)
+pub fn current_exe_string() -> io::Result<String> {
+    env::current_exe().map(path_buf_to_str)
+}
+
+/// Returns the arguments of the process, converted lossily.
+///
+/// Unlike [`std::env::args`](https://doc.rust-lang.org/std/env/fn.args.html)
+/// this does not panic on an argument which is not utf8. The first one is
+/// normally the program, see [`args_vec_lossy_skip_program`]. A process
+/// started with no arguments at all gets an empty `Vec`.
+pub fn args_vec_lossy() -> Vec<String> {
+    args_to_strings(env::args_os())
+}
+
+/// Same as [`args_vec_lossy`] without the first argument, the program
+pub fn args_vec_lossy_skip_program() -> Vec<String> {
+    args_to_strings(env::args_os().skip(1))
+}
+
+/// Returns the temporary directory as a `String`.
+///
+/// See [`std::env::temp_dir`](https://doc.rust-lang.org/std/env/fn.temp_dir.html)
+pub fn temp_dir_string() -> String {
+    path_buf_to_str(env::temp_dir())
+}
+
+impl IntoString for VarError {
+    /// Special Case
+    ///
+    /// `NotUnicode` holds the value of the variable, that is converted
+    /// (keeping its buffer when it is valid utf8 after all). `NotPresent`
+    /// is an empty `String`, see [`var_error_into_option_string`] to tell
+    /// it apart from a variable which is set but empty.
+    fn into_string(self) -> String {
+        var_error_into_option_string(self).unwrap_or_default()
+    }
+}
+impl Sealed for VarError {}
+impl IntoStringBase for VarError {
+    fn ref_into_string(&self) -> String {
+        match self {
+            VarError::NotUnicode(x) => x.ref_into_string(),
+            VarError::NotPresent => String::new(),
+        }
+    }
+}
+
+/// The value held by `NotUnicode` converted lossily, `None` for `NotPresent`.
+///
+/// With [`std::env::var`](https://doc.rust-lang.org/std/env/fn.var.html),
+/// `env::var(key).map(Some).unwrap_or_else(var_error_into_option_string)`
+/// reads a variable lossily, `None` only when it is not set.
+pub fn var_error_into_option_string(err: VarError) -> Option<String> {
+    match err {
+        VarError::NotUnicode(x) => Some(<OsString as IntoString>::into_string(x)),
+        VarError::NotPresent => None,
+    }
+}
+
+/// Splits an environment block (`KEY=value\0KEY2=value2\0\0`) into its
+/// variables, each half converted lossily.
+///
+/// The block ends at the first empty entry (the double NUL), or at the end
+/// of `bytes` when that is missing. Each entry is split on its first `=`,
+/// not counting a leading one, so the `=C:=C:\dir` entries Windows keeps
+/// for the current directory of each drive have the key `=C:`. An entry
+/// without an `=` is the key, with an empty value.
+pub fn env_block_into_strings(bytes: &[u8]) -> Vec<(String, String)> {
+    bytes
+        .split(|x| *x == 0)
+        .take_while(|x| !x.is_empty())
+        .map(|entry| {
+            let (key, value) = split_entry(entry, b'=');
+            (local_to_cow("[u8]", key).into_owned(), local_to_cow("[u8]", value).into_owned())
+        })
+        .collect()
+}
+
+/// Same as [`env_block_into_strings`] for a utf16 block, such as
+/// `GetEnvironmentStringsW` returns. Unpaired surrogates become `U+FFFD`.
+pub fn env_block_wide_into_strings(units: &[u16]) -> Vec<(String, String)> {
+    units
+        .split(|x| *x == 0)
+        .take_while(|x| !x.is_empty())
+        .map(|entry| {
+            let (key, value) = split_entry(entry, u16::from(b'='));
+            (local_wide_to_str(key), local_wide_to_str(value))
+        })
+        .collect()
+}
+
+// splits on the first `eq` after the first unit, the value is empty when
+// there is none
+fn split_entry<T: PartialEq>(entry: &[T], eq: T) -> (&[T], &[T]) {
+    match entry.iter().skip(1).position(|x| *x == eq) {
+        Some(at) => (&entry[..at + 1], &entry[at + 2..]),
+        None => (entry, &[]),
+    }
+}
+
+fn args_to_strings(args: impl IntoIterator<Item = OsString>) -> Vec<String> {
+    args.into_iter().map(<OsString as IntoString>::into_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn path_helpers_are_not_empty() {
+        assert!(!current_dir_string().unwrap().is_empty());
+        assert!(!current_exe_string().unwrap().is_empty());
+        assert!(!temp_dir_string().is_empty());
+    }
+
+    #[test]
+    fn valid_path_keeps_its_buffer() {
+        let path = PathBuf::from("/tmp/dir");
+        let ptr = path.as_os_str().as_encoded_bytes().as_ptr();
+        let text = path_buf_to_str(path);
+        assert_eq!(text, "/tmp/dir");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn invalid_path_is_lossy() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let path = PathBuf::from(OsString::from_vec(b"/tmp/\xFFdir".to_vec()));
+        assert_eq!(path_buf_to_str(path), "/tmp/\u{FFFD}dir");
+    }
+
+    #[test]
+    fn var_errors() {
+        assert_eq!(VarError::NotPresent.into_string(), "");
+        assert_eq!(var_error_into_option_string(VarError::NotPresent), None);
+        let value = OsString::from("valid after all");
+        let ptr = value.as_encoded_bytes().as_ptr();
+        let text = VarError::NotUnicode(value).into_string();
+        assert_eq!(text, "valid after all");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn var_error_not_unicode() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let err = VarError::NotUnicode(OsString::from_vec(b"a\xFFb".to_vec()));
+        assert_eq!((&err).into_string(), "a\u{FFFD}b");
+        assert_eq!(var_error_into_option_string(err.clone()), Some("a\u{FFFD}b".to_string()));
+        assert_eq!(err.into_string(), "a\u{FFFD}b");
+    }
+
+    // `(key, value)` pairs as owned strings
+    fn pairs(x: &[(&str, &str)]) -> Vec<(String, String)> {
+        x.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn env_blocks() {
+        let block = b"PATH=/usr/bin\0HOME=/root\0\0";
+        assert_eq!(env_block_into_strings(block), pairs(&[("PATH", "/usr/bin"), ("HOME", "/root")]));
+        // only the first `=` splits
+        assert_eq!(env_block_into_strings(b"OPTS=a=b=c\0\0"), pairs(&[("OPTS", "a=b=c")]));
+        assert_eq!(env_block_into_strings(b"BAD=a\xFFb\0\0"), pairs(&[("BAD", "a\u{FFFD}b")]));
+        // no `=` at all, and a drive directory from Windows
+        assert_eq!(env_block_into_strings(b"JUSTKEY\0=C:=C:\\dir\0\0"), pairs(&[("JUSTKEY", ""), ("=C:", "C:\\dir")]));
+        // no terminator, or nothing after it
+        assert_eq!(env_block_into_strings(b"A=1\0B=2"), pairs(&[("A", "1"), ("B", "2")]));
+        assert_eq!(env_block_into_strings(b"A=1\0\0B=2\0\0"), pairs(&[("A", "1")]));
+        assert!(env_block_into_strings(b"\0").is_empty());
+        assert!(env_block_into_strings(b"").is_empty());
+    }
+
+    #[test]
+    fn wide_env_blocks() {
+        let block: Vec<u16> = "PATH=C:\\bin\0OPTS=x=y\0=D:=D:\\\0\0".encode_utf16().collect();
+        assert_eq!(
+            env_block_wide_into_strings(&block),
+            pairs(&[("PATH", "C:\\bin"), ("OPTS", "x=y"), ("=D:", "D:\\")]),
+        );
+        let lone = [u16::from(b'K'), u16::from(b'='), 0xD800, 0, 0];
+        assert_eq!(env_block_wide_into_strings(&lone), pairs(&[("K", "\u{FFFD}")]));
+    }
+
+    #[test]
+    fn args_keep_their_order() {
+        let args = ["prog", "-v", "", "file name"].map(OsString::from);
+        assert_eq!(args_to_strings(args), ["prog", "-v", "", "file name"]);
+        assert_eq!(args_to_strings(Vec::new()), Vec::<String>::new());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn args_not_unicode() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let args = [b"prog".to_vec(), b"\xFF".to_vec(), b"ok".to_vec(), b"a\xC3".to_vec()].map(OsString::from_vec);
+        assert_eq!(args_to_strings(args), ["prog", "\u{FFFD}", "ok", "a\u{FFFD}"]);
+    }
+
+    #[test]
+    fn args_of_this_process() {
+        let all = args_vec_lossy();
+        assert!(!all.is_empty());
+        assert_eq!(args_vec_lossy_skip_program(), all[1..]);
+    }
+}
@@ -0,0 +1,99 @@
+//! `SystemTime` as a count since the Unix epoch.
+
+use std::time::{SystemTime,UNIX_EPOCH};
+
+use super::{IntoString,IntoStringBase,sealed::Sealed};
+
+/// The unit [`system_time_epoch_string`] counts in
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum EpochUnit {
+    #[default]
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl EpochUnit {
+    fn nanos(self) -> i128 {
+        match self {
+            EpochUnit::Seconds => 1_000_000_000,
+            EpochUnit::Millis => 1_000_000,
+            EpochUnit::Micros => 1_000,
+            EpochUnit::Nanos => 1,
+        }
+    }
+}
+
+/// Writes `t` as a whole number of `unit`s since `1970-01-01T00:00:00Z`.
+///
+/// Times before the epoch are negative. Part of a unit is rounded down,
+/// the same as `chrono` and `time` do, so half a second before the epoch
+/// is `-1` in seconds and every value stands for the unit which starts at
+/// it. The count is clamped to the range of `i64`, which is what most
+/// consumers parse it into, nanoseconds run out in 2262.
+pub fn system_time_epoch_string(t: SystemTime, unit: EpochUnit) -> String {
+    // i128 holds any `SystemTime` in nanoseconds
+    let nanos = match t.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    };
+    let count = nanos.div_euclid(unit.nanos());
+    count.clamp(i64::MIN.into(), i64::MAX.into()).to_string()
+}
+
+/// A `SystemTime` which converts with [`system_time_epoch_string`] in
+/// seconds, for sinks which take an `IntoString`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct EpochSecs(pub SystemTime);
+
+impl IntoString for EpochSecs {
+    fn into_string(self) -> String {
+        system_time_epoch_string(self.0, EpochUnit::Seconds)
+    }
+}
+impl Sealed for EpochSecs {}
+impl IntoStringBase for EpochSecs {
+    fn ref_into_string(&self) -> String {
+        system_time_epoch_string(self.0, EpochUnit::Seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn every_unit(t: SystemTime) -> [String; 4] {
+        [EpochUnit::Seconds, EpochUnit::Millis, EpochUnit::Micros, EpochUnit::Nanos].map(|unit| system_time_epoch_string(t, unit))
+    }
+
+    #[test]
+    fn the_epoch_and_around_it() {
+        assert_eq!(every_unit(UNIX_EPOCH), ["0", "0", "0", "0"]);
+        assert_eq!(every_unit(UNIX_EPOCH - Duration::from_secs(1)), ["-1", "-1000", "-1000000", "-1000000000"]);
+        // rounded down, before the epoch as well
+        assert_eq!(every_unit(UNIX_EPOCH - Duration::from_millis(500)), ["-1", "-500", "-500000", "-500000000"]);
+        assert_eq!(every_unit(UNIX_EPOCH + Duration::from_nanos(1_999)), ["0", "0", "1", "1999"]);
+    }
+
+    #[test]
+    fn known_time() {
+        // 2001-09-09T01:46:40.123Z
+        let t = UNIX_EPOCH + Duration::from_millis(1_000_000_000_123);
+        assert_eq!(system_time_epoch_string(t, EpochUnit::Millis), "1000000000123");
+        assert_eq!(system_time_epoch_string(t, EpochUnit::Seconds), "1000000000");
+        assert_eq!(EpochSecs(t).into_string(), "1000000000");
+        assert_eq!((&EpochSecs(t)).into_string(), "1000000000");
+    }
+
+    #[test]
+    fn nanos_saturate() {
+        // past 2262-04-11 nanoseconds no longer fit an i64
+        let t = UNIX_EPOCH + Duration::from_secs(i64::MAX as u64 / 1_000_000_000 + 1);
+        assert_eq!(system_time_epoch_string(t, EpochUnit::Nanos), i64::MAX.to_string());
+        assert_eq!(system_time_epoch_string(t, EpochUnit::Seconds), "9223372037");
+        let t = UNIX_EPOCH - Duration::from_secs(i64::MAX as u64 / 1_000_000_000 + 1);
+        assert_eq!(system_time_epoch_string(t, EpochUnit::Nanos), i64::MIN.to_string());
+    }
+}
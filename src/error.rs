@@ -0,0 +1,59 @@
+//! Rendering an error together with its
+//! [`source`](https://doc.rust-lang.org/std/error/trait.Error.html#method.source)
+//! chain as a `String`.
+
+use std::{
+    error::Error,
+    fmt::{Display,Write},
+    iter,
+};
+
+/// How [`error_chain_string`] lays out the chain
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub enum Style {
+    /// Every message on one line joined with `": "`, `outer: inner`
+    #[default]
+    SingleLine,
+    /// The outer message, then a `Caused by:` list of the sources (same
+    /// as anyhow's `{:?}` without the backtrace)
+    MultiLine,
+}
+
+/// Renders `err` followed by each of its sources in `style`
+pub fn error_chain_string(err: &(dyn Error + 'static), style: Style) -> String {
+    chain_string(iter::successors(Some(err), |&e| e.source()), style)
+}
+
+// every error in the chain, outermost first
+pub(crate) fn chain_string<I>(chain: I, style: Style) -> String
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    let mut chain = chain.into_iter();
+    let mut out = String::new();
+    if let Some(first) = chain.next() {
+        let _ = write!(out, "{}", first);
+    }
+    match style {
+        Style::SingleLine => {
+            for e in chain {
+                let _ = write!(out, ": {}", e);
+            }
+        }
+        Style::MultiLine => {
+            let causes: Vec<String> = chain.map(|e| e.to_string()).collect();
+            if !causes.is_empty() {
+                out.push_str("\n\nCaused by:");
+            }
+            if let [only] = causes.as_slice() {
+                let _ = write!(out, "\n    {}", only);
+            } else {
+                for (n, e) in causes.iter().enumerate() {
+                    let _ = write!(out, "\n    {}: {}", n, e);
+                }
+            }
+        }
+    }
+    out
+}
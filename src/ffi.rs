@@ -0,0 +1,556 @@
+//! Helpers for [`std::ffi`](https://doc.rust-lang.org/std/ffi/index.html) types.
+//!
+//! # Raw pointers
+//!
+//! The `unsafe` helpers which take a raw pointer are named after what it
+//! points to, `c_ptr_*` for a NUL terminated `char *`, `wide_ptr_*` for
+//! NUL terminated utf16 (a Windows `wchar_t *`) and `bytes_ptr_*` (or
+//! `c_char_ptr_*`, `i8_ptr_*`) for a pointer with a length. Each comes as
+//!
+//! * `*_into_string`, which is `None` for a null pointer
+//! * `*_into_string_or_default`, which is `""` for a null pointer
+//!
+//! and `c_nonnull_into_string`, `wide_nonnull_into_string` and
+//! `bytes_nonnull_into_string` take a `NonNull`, for callers which already
+//! checked.
+
+use alloc::{
+    string::String,
+    vec::Vec,
+};
+use core::{
+    ffi::{CStr,c_char},
+    ptr::NonNull,
+};
+#[cfg(feature = "std")]
+use std::ffi::OsStr;
+
+#[cfg(feature = "std")]
+use super::IntoString;
+use super::{local_to_cow,local_to_str,local_wide_to_str};
+
+/// Extension trait for [`OsStr`](https://doc.rust-lang.org/std/ffi/struct.OsStr.html)
+///
+/// Matching is done against the raw representation of the `OsStr`
+/// (the bytes on Unix, WTF-8 on Windows) _before_ anything is made lossy,
+/// so a `U+FFFD` in the pattern never matches an invalid sequence in
+/// the `OsStr`. Only the remaining part is converted.
+#[cfg(feature = "std")]
+pub trait OsStrStringExt {
+
+    /// Returns `true` if the `OsStr` begins with `prefix`
+    fn starts_with_str(&self, prefix: &str) -> bool;
+
+    /// Returns `true` if the `OsStr` ends with `suffix`
+    fn ends_with_str(&self, suffix: &str) -> bool;
+
+    /// Removes `prefix` and converts what remains,
+    /// `None` if the `OsStr` does not begin with `prefix`
+    fn strip_prefix_string(&self, prefix: &str) -> Option<String>;
+
+    /// Removes `suffix` and converts what remains,
+    /// `None` if the `OsStr` does not end with `suffix`
+    fn strip_suffix_string(&self, suffix: &str) -> Option<String>;
+
+    /// The normal lossy conversion, same as `into_string`
+    fn replace_invalid(&self) -> String;
+
+    /// Splits on every `delim` and converts each piece on its own, for
+    /// `PATH` like values. An empty piece (between two `delim` in a row, or
+    /// after a trailing one) is kept as `""`.
+    ///
+    /// # Panics
+    ///
+    /// When `delim` is not ASCII, anything else could split a sequence in
+    /// two on Windows.
+    fn split_lossy(&self, delim: u8) -> SplitLossy<'_>;
+}
+
+/// The iterator returned by [`OsStrStringExt::split_lossy`]
+#[cfg(feature = "std")]
+#[derive(Clone,Debug)]
+pub struct SplitLossy<'a> {
+    // `None` once the last piece was returned
+    rest: Option<&'a [u8]>,
+    delim: u8,
+}
+
+#[cfg(feature = "std")]
+impl Iterator for SplitLossy<'_> {
+    type Item = String;
+    fn next(&mut self) -> Option<String> {
+        let rest = self.rest?;
+        let piece = match rest.iter().position(|x| *x == self.delim) {
+            Some(at) => {
+                self.rest = Some(&rest[at + 1..]);
+                &rest[..at]
+            }
+            None => {
+                self.rest = None;
+                rest
+            }
+        };
+        // SAFETY: split on an ASCII byte, checked by `split_lossy`
+        Some(unsafe { OsStr::from_encoded_bytes_unchecked(piece) }.into_string())
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::iter::FusedIterator for SplitLossy<'_> { }
+
+#[cfg(feature = "std")]
+impl OsStrStringExt for OsStr {
+    fn starts_with_str(&self, prefix: &str) -> bool {
+        self.as_encoded_bytes().starts_with(prefix.as_bytes())
+    }
+    fn ends_with_str(&self, suffix: &str) -> bool {
+        self.as_encoded_bytes().ends_with(suffix.as_bytes())
+    }
+    fn strip_prefix_string(&self, prefix: &str) -> Option<String> {
+        let rest = self.as_encoded_bytes().strip_prefix(prefix.as_bytes())?;
+        // SAFETY: `rest` is split off directly after a valid utf8 str
+        Some(unsafe { OsStr::from_encoded_bytes_unchecked(rest) }.into_string())
+    }
+    fn strip_suffix_string(&self, suffix: &str) -> Option<String> {
+        let rest = self.as_encoded_bytes().strip_suffix(suffix.as_bytes())?;
+        // SAFETY: `rest` is split off directly before a valid utf8 str
+        Some(unsafe { OsStr::from_encoded_bytes_unchecked(rest) }.into_string())
+    }
+    fn replace_invalid(&self) -> String {
+        self.into_string()
+    }
+    fn split_lossy(&self, delim: u8) -> SplitLossy<'_> {
+        assert!(delim.is_ascii(), "split_lossy delimiter must be ASCII, got {:#04x}", delim);
+        SplitLossy { rest: Some(self.as_encoded_bytes()), delim }
+    }
+}
+
+/// Converts a fixed size `c_char` array (such as the fields of `utsname`)
+/// into a `String`.
+///
+/// The array ends at the first NUL, or is used whole when there is none.
+/// It is then converted the same as a `&CStr`.
+pub fn c_char_array_into_string(arr: &[c_char]) -> String {
+    // SAFETY: `c_char` is `i8` or `u8`, both are one byte
+    let bytes = unsafe { core::slice::from_raw_parts(arr.as_ptr().cast::<u8>(), arr.len()) };
+    until_nul(bytes)
+}
+
+/// Same as [`c_char_array_into_string`] for `i8` arrays, for code which
+/// holds `i8` whatever the signedness of `c_char` on the target
+pub fn i8_array_into_string(arr: &[i8]) -> String {
+    // SAFETY: `i8` and `u8` have the same size and alignment
+    let bytes = unsafe { core::slice::from_raw_parts(arr.as_ptr().cast::<u8>(), arr.len()) };
+    until_nul(bytes)
+}
+
+/// Converts the NUL terminated string at `ptr` the same as a `&CStr`,
+/// `None` for a null pointer.
+///
+/// # Safety
+///
+/// When not null `ptr` must point to a NUL terminated string which stays
+/// valid and unchanged for the duration of the call.
+pub unsafe fn c_ptr_into_string(ptr: *const c_char) -> Option<String> {
+    // SAFETY: not null, the rest is up to the caller
+    NonNull::new(ptr.cast_mut()).map(|x| unsafe { c_nonnull_into_string(x) })
+}
+
+/// Same as [`c_ptr_into_string`], with `""` for a null pointer
+///
+/// # Safety
+///
+/// Same as [`c_ptr_into_string`].
+pub unsafe fn c_ptr_into_string_or_default(ptr: *const c_char) -> String {
+    // SAFETY: same contract
+    unsafe { c_ptr_into_string(ptr) }.unwrap_or_default()
+}
+
+/// Same as [`c_ptr_into_string`], for a pointer which is already known
+/// not to be null
+///
+/// # Safety
+///
+/// `ptr` must point to a NUL terminated string which stays valid and
+/// unchanged for the duration of the call.
+pub unsafe fn c_nonnull_into_string(ptr: NonNull<c_char>) -> String {
+    // SAFETY: up to the caller
+    let text = unsafe { CStr::from_ptr(ptr.as_ptr()) };
+    local_to_str("*const c_char", text.to_bytes())
+}
+
+/// Converts the NUL terminated utf16 at `ptr` (a `wchar_t *` on Windows),
+/// `None` for a null pointer.
+///
+/// Unpaired surrogates become `U+FFFD`.
+///
+/// # Safety
+///
+/// When not null `ptr` must point to NUL terminated utf16 which stays
+/// valid and unchanged for the duration of the call.
+pub unsafe fn wide_ptr_into_string(ptr: *const u16) -> Option<String> {
+    // SAFETY: not null, the rest is up to the caller
+    NonNull::new(ptr.cast_mut()).map(|x| unsafe { wide_nonnull_into_string(x) })
+}
+
+/// Same as [`wide_ptr_into_string`], with `""` for a null pointer
+///
+/// # Safety
+///
+/// Same as [`wide_ptr_into_string`].
+pub unsafe fn wide_ptr_into_string_or_default(ptr: *const u16) -> String {
+    // SAFETY: same contract
+    unsafe { wide_ptr_into_string(ptr) }.unwrap_or_default()
+}
+
+/// Same as [`wide_ptr_into_string`], for a pointer which is already known
+/// not to be null
+///
+/// # Safety
+///
+/// `ptr` must point to NUL terminated utf16 which stays valid and
+/// unchanged for the duration of the call.
+pub unsafe fn wide_nonnull_into_string(ptr: NonNull<u16>) -> String {
+    let ptr = ptr.as_ptr().cast_const();
+    let mut len = 0;
+    // SAFETY: every unit up to the NUL is readable
+    while unsafe { *ptr.add(len) } != 0 {
+        len += 1;
+    }
+    // SAFETY: the `len` units before the NUL were just read
+    local_wide_to_str(unsafe { core::slice::from_raw_parts(ptr, len) })
+}
+
+/// Converts the `len` bytes at `ptr`, for C APIs which return a pointer
+/// and a length instead of a NUL terminated string.
+///
+/// The bytes are converted the same as a `Vec<u8>`, only the bad
+/// sequences are replaced and a NUL is kept as it is. A `len` of 0 is
+/// `Some("")` whatever `ptr` is, a null `ptr` with any other `len` is
+/// `None`.
+///
+/// # Safety
+///
+/// When `len` is not 0 and `ptr` is not null, `ptr` must point to `len`
+/// readable bytes which stay valid and unchanged for the duration of the
+/// call.
+pub unsafe fn bytes_ptr_into_string(ptr: *const u8, len: usize) -> Option<String> {
+    if len == 0 {
+        return Some(String::new());
+    }
+    // SAFETY: not null, the rest is up to the caller
+    NonNull::new(ptr.cast_mut()).map(|x| unsafe { bytes_nonnull_into_string(x, len) })
+}
+
+/// Same as [`bytes_ptr_into_string`], with `""` for a null pointer
+///
+/// # Safety
+///
+/// Same as [`bytes_ptr_into_string`].
+pub unsafe fn bytes_ptr_into_string_or_default(ptr: *const u8, len: usize) -> String {
+    // SAFETY: same contract
+    unsafe { bytes_ptr_into_string(ptr, len) }.unwrap_or_default()
+}
+
+/// Same as [`bytes_ptr_into_string`], for a pointer which is already
+/// known not to be null
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes which stay valid and
+/// unchanged for the duration of the call.
+pub unsafe fn bytes_nonnull_into_string(ptr: NonNull<u8>, len: usize) -> String {
+    // SAFETY: up to the caller
+    let bytes = unsafe { core::slice::from_raw_parts(ptr.as_ptr().cast_const(), len) };
+    local_to_cow("*const u8", bytes).into_owned()
+}
+
+/// Same as [`bytes_ptr_into_string`] for a `c_char` pointer
+///
+/// # Safety
+///
+/// Same as [`bytes_ptr_into_string`].
+pub unsafe fn c_char_ptr_into_string(ptr: *const c_char, len: usize) -> Option<String> {
+    // SAFETY: same contract, `c_char` is one byte
+    unsafe { bytes_ptr_into_string(ptr.cast::<u8>(), len) }
+}
+
+/// Same as [`c_char_ptr_into_string`], with `""` for a null pointer
+///
+/// # Safety
+///
+/// Same as [`bytes_ptr_into_string`].
+pub unsafe fn c_char_ptr_into_string_or_default(ptr: *const c_char, len: usize) -> String {
+    // SAFETY: same contract
+    unsafe { c_char_ptr_into_string(ptr, len) }.unwrap_or_default()
+}
+
+/// Same as [`bytes_ptr_into_string`] for an `i8` pointer, for code which
+/// holds `i8` whatever the signedness of `c_char` on the target
+///
+/// # Safety
+///
+/// Same as [`bytes_ptr_into_string`].
+pub unsafe fn i8_ptr_into_string(ptr: *const i8, len: usize) -> Option<String> {
+    // SAFETY: same contract, `i8` is one byte
+    unsafe { bytes_ptr_into_string(ptr.cast::<u8>(), len) }
+}
+
+/// Same as [`i8_ptr_into_string`], with `""` for a null pointer
+///
+/// # Safety
+///
+/// Same as [`bytes_ptr_into_string`].
+pub unsafe fn i8_ptr_into_string_or_default(ptr: *const i8, len: usize) -> String {
+    // SAFETY: same contract
+    unsafe { i8_ptr_into_string(ptr, len) }.unwrap_or_default()
+}
+
+/// Converts a list of NUL terminated utf16 strings which ends with an
+/// extra NUL, such as a `REG_MULTI_SZ` registry value.
+///
+/// The list ends at the first empty entry (the double NUL), an entry which
+/// runs to the end of `units` without a NUL is still included. Unpaired
+/// surrogates become `U+FFFD`.
+pub fn multi_wide_into_strings(units: &[u16]) -> Vec<String> {
+    units
+        .split(|x| *x == 0)
+        .take_while(|x| !x.is_empty())
+        .map(local_wide_to_str)
+        .collect()
+}
+
+/// Same as [`multi_wide_into_strings`] for a pointer to the list, `None`
+/// for a null pointer.
+///
+/// # Safety
+///
+/// When not null `ptr` must point to a list which ends with two NULs in a
+/// row (or starts with a NUL, for an empty list), which stays valid and
+/// unchanged for the duration of the call.
+pub unsafe fn multi_wide_ptr_into_strings(ptr: *const u16) -> Option<Vec<String>> {
+    if ptr.is_null() {
+        return None;
+    }
+    let mut len = 0;
+    // SAFETY: every unit up to the terminator is readable
+    while unsafe { *ptr.add(len) } != 0 || (len > 0 && unsafe { *ptr.add(len - 1) } != 0) {
+        len += 1;
+    }
+    // SAFETY: the `len` units before the terminator were just read
+    Some(multi_wide_into_strings(unsafe { core::slice::from_raw_parts(ptr, len) }))
+}
+
+/// Same as [`multi_wide_ptr_into_strings`], with an empty list for a null
+/// pointer
+///
+/// # Safety
+///
+/// Same as [`multi_wide_ptr_into_strings`].
+pub unsafe fn multi_wide_ptr_into_strings_or_default(ptr: *const u16) -> Vec<String> {
+    // SAFETY: same contract
+    unsafe { multi_wide_ptr_into_strings(ptr) }.unwrap_or_default()
+}
+
+fn until_nul(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    local_to_str("[c_char]", &bytes[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_str_prefix_and_suffix() {
+        let name = OsStr::new("prefix-name.txt");
+        assert!(name.starts_with_str("prefix-"));
+        assert!(name.ends_with_str(".txt"));
+        assert!(!name.starts_with_str("name"));
+        assert_eq!(name.strip_prefix_string("prefix-").as_deref(), Some("name.txt"));
+        assert_eq!(name.strip_suffix_string(".txt").as_deref(), Some("prefix-name"));
+        assert_eq!(name.strip_prefix_string("other"), None);
+        assert_eq!(name.replace_invalid(), "prefix-name.txt");
+    }
+
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn os_str_invalid_after_prefix() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"pre\xFFfix\xFE");
+        assert_eq!(name.strip_prefix_string("pre").as_deref(), Some("\u{FFFD}fix\u{FFFD}"));
+        assert_eq!(name.strip_suffix_string("fix"), None);
+        // the replacement character never matches the bytes it replaces
+        assert!(!name.starts_with_str("pre\u{FFFD}"));
+        assert_eq!(name.strip_prefix_string("pre\u{FFFD}"), None);
+        assert_eq!(name.replace_invalid(), "pre\u{FFFD}fix\u{FFFD}");
+    }
+
+    // `c_char` is `i8` or `u8` depending on the target
+    fn c_chars(bytes: &[u8]) -> Vec<c_char> {
+        bytes.iter().map(|&b| b as c_char).collect()
+    }
+
+    #[test]
+    fn c_char_array_nul_mid_way() {
+        assert_eq!(c_char_array_into_string(&c_chars(b"eth0\0\0junk")), "eth0");
+        assert_eq!(c_char_array_into_string(&c_chars(b"\0after")), "");
+        assert_eq!(i8_array_into_string(&[104, 105, 0, 120]), "hi");
+    }
+
+    #[test]
+    fn c_char_array_without_nul() {
+        assert_eq!(c_char_array_into_string(&c_chars(b"full")), "full");
+        assert_eq!(c_char_array_into_string(&[]), "");
+        assert_eq!(i8_array_into_string(&[111, 107]), "ok");
+    }
+
+    #[test]
+    fn c_char_array_invalid_before_nul() {
+        // like `into_string` for a `CStr`, every byte is replaced
+        assert_eq!(c_char_array_into_string(&c_chars(b"a\xFF\0\xFE")), "\u{FFFD}\u{FFFD}");
+        assert_eq!(i8_array_into_string(&[-1, 0]), "\u{FFFD}");
+    }
+
+    #[test]
+    fn bytes_ptr_valid_and_invalid() {
+        let valid = "h\u{e9}\0llo".as_bytes().to_vec();
+        let invalid = b"a\xFFb\xE2\x82".to_vec();
+        // SAFETY: the pointers and lengths of live `Vec`s
+        unsafe {
+            assert_eq!(bytes_ptr_into_string(valid.as_ptr(), valid.len()).as_deref(), Some("h\u{e9}\0llo"));
+            assert_eq!(bytes_ptr_into_string(invalid.as_ptr(), invalid.len()).as_deref(), Some("a\u{FFFD}b\u{FFFD}"));
+            // only the first `len` bytes
+            assert_eq!(bytes_ptr_into_string(invalid.as_ptr(), 1).as_deref(), Some("a"));
+            let ptr = NonNull::new(invalid.as_ptr().cast_mut()).unwrap();
+            assert_eq!(bytes_nonnull_into_string(ptr, 2), "a\u{FFFD}");
+        }
+    }
+
+    #[test]
+    fn bytes_ptr_null_and_empty() {
+        let bytes = b"abc".to_vec();
+        // SAFETY: null or a length of 0 is never read
+        unsafe {
+            assert_eq!(bytes_ptr_into_string(core::ptr::null(), 0).as_deref(), Some(""));
+            assert_eq!(bytes_ptr_into_string(bytes.as_ptr(), 0).as_deref(), Some(""));
+            assert_eq!(bytes_ptr_into_string(core::ptr::null(), 3), None);
+            assert_eq!(bytes_ptr_into_string_or_default(core::ptr::null(), 3), "");
+            assert_eq!(c_char_ptr_into_string(core::ptr::null(), 3), None);
+            assert_eq!(c_char_ptr_into_string_or_default(core::ptr::null(), 0), "");
+            assert_eq!(i8_ptr_into_string(core::ptr::null(), 1), None);
+            assert_eq!(i8_ptr_into_string_or_default(core::ptr::null(), 1), "");
+        }
+    }
+
+    #[test]
+    fn c_char_and_i8_ptr() {
+        let chars = c_chars(b"ok\xFF");
+        let signed = [111i8, 107, -1];
+        // SAFETY: the pointers and lengths of live `Vec`s
+        unsafe {
+            assert_eq!(c_char_ptr_into_string(chars.as_ptr(), chars.len()).as_deref(), Some("ok\u{FFFD}"));
+            assert_eq!(c_char_ptr_into_string_or_default(chars.as_ptr(), 2), "ok");
+            assert_eq!(i8_ptr_into_string(signed.as_ptr(), signed.len()).as_deref(), Some("ok\u{FFFD}"));
+            assert_eq!(i8_ptr_into_string_or_default(signed.as_ptr(), 2), "ok");
+        }
+    }
+
+    // `text` as utf16
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    #[test]
+    fn multi_wide_lists() {
+        assert_eq!(multi_wide_into_strings(&wide("first\0second\0\0")), ["first", "second"]);
+        assert!(multi_wide_into_strings(&wide("\0")).is_empty());
+        assert!(multi_wide_into_strings(&[]).is_empty());
+        // anything after the terminator is not part of the list
+        assert_eq!(multi_wide_into_strings(&wide("a\0\0b\0\0")), ["a"]);
+        let mut lone = wide("x\0");
+        lone.extend([0xD800, u16::from(b'y'), 0, 0]);
+        assert_eq!(multi_wide_into_strings(&lone), ["x", "\u{FFFD}y"]);
+    }
+
+    #[test]
+    fn multi_wide_cut_short() {
+        assert_eq!(multi_wide_into_strings(&wide("a\0b\0")), ["a", "b"]);
+        assert_eq!(multi_wide_into_strings(&wide("a\0b")), ["a", "b"]);
+    }
+
+    #[test]
+    fn multi_wide_ptr() {
+        let list = wide("first\0second\0\0");
+        let empty = wide("\0");
+        // SAFETY: both lists end with their terminator
+        unsafe {
+            assert_eq!(multi_wide_ptr_into_strings(list.as_ptr()).unwrap(), ["first", "second"]);
+            assert_eq!(multi_wide_ptr_into_strings(empty.as_ptr()), Some(Vec::new()));
+            assert_eq!(multi_wide_ptr_into_strings(core::ptr::null()), None);
+            assert!(multi_wide_ptr_into_strings_or_default(core::ptr::null()).is_empty());
+        }
+    }
+
+    #[test]
+    fn c_ptr_family() {
+        // SAFETY: null, or NUL terminated literals
+        unsafe {
+            assert_eq!(c_ptr_into_string(c"text".as_ptr()).as_deref(), Some("text"));
+            assert_eq!(c_ptr_into_string(c"".as_ptr()).as_deref(), Some(""));
+            assert_eq!(c_ptr_into_string(core::ptr::null()), None);
+            assert_eq!(c_ptr_into_string_or_default(c"text".as_ptr()), "text");
+            assert_eq!(c_ptr_into_string_or_default(core::ptr::null()), "");
+            let ptr = NonNull::new(c"a\xFF".as_ptr().cast_mut()).unwrap();
+            assert_eq!(c_nonnull_into_string(ptr), "\u{FFFD}\u{FFFD}");
+        }
+    }
+
+    #[test]
+    fn wide_ptr_family() {
+        let text = wide("t\u{e9}xt\0");
+        let mut lone = wide("a");
+        lone.extend([0xDC00, 0]);
+        // SAFETY: null, or NUL terminated buffers
+        unsafe {
+            assert_eq!(wide_ptr_into_string(text.as_ptr()).as_deref(), Some("t\u{e9}xt"));
+            assert_eq!(wide_ptr_into_string([0u16].as_ptr()).as_deref(), Some(""));
+            assert_eq!(wide_ptr_into_string(core::ptr::null()), None);
+            assert_eq!(wide_ptr_into_string_or_default(text.as_ptr()), "t\u{e9}xt");
+            assert_eq!(wide_ptr_into_string_or_default(core::ptr::null()), "");
+            let ptr = NonNull::new(lone.as_mut_ptr()).unwrap();
+            assert_eq!(wide_nonnull_into_string(ptr), "a\u{FFFD}");
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn split_lossy_keeps_empty_fields() {
+        let path = OsStr::new("/usr/bin::/bin:");
+        assert_eq!(path.split_lossy(b':').collect::<Vec<_>>(), ["/usr/bin", "", "/bin", ""]);
+        assert_eq!(OsStr::new("").split_lossy(b':').collect::<Vec<_>>(), [""]);
+        assert_eq!(OsStr::new(":").split_lossy(b':').collect::<Vec<_>>(), ["", ""]);
+        assert_eq!(OsStr::new("one").split_lossy(b':').collect::<Vec<_>>(), ["one"]);
+    }
+
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn split_lossy_invalid_component() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = OsStr::from_bytes(b"/usr/bin:/opt/\xFF\xC3:/bin");
+        assert_eq!(path.split_lossy(b':').collect::<Vec<_>>(), ["/usr/bin", "/opt/\u{FFFD}\u{FFFD}", "/bin"]);
+        // `\xC3` does not take the `:` after it
+        let path = OsStr::from_bytes(b"a\xC3:b");
+        assert_eq!(path.split_lossy(b':').collect::<Vec<_>>(), ["a\u{FFFD}", "b"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "must be ASCII")]
+    fn split_lossy_non_ascii_delimiter() {
+        OsStr::new("a").split_lossy(0xC3);
+    }
+}
@@ -0,0 +1,149 @@
+//! Lossy conversion into fixed capacity strings, which can't allocate.
+//!
+//! The output is written piece by piece, so the lossy replacement is the
+//! same as `into_string` without ever building the `String`.
+
+use core::{
+    ffi::CStr,
+    fmt::{self,Write},
+};
+
+use super::{count_conversion,invalid_runs,local_validate,lossy};
+
+// a fixed capacity string the output is written into
+pub(crate) trait FixedBuf {
+    // appends all of `s` when it fits, otherwise nothing
+    fn try_push_str(&mut self, s: &str) -> bool;
+}
+
+// the output did not fit
+#[derive(Clone,Copy,Debug)]
+pub(crate) struct Full;
+
+pub(crate) struct Writer<'a, B: FixedBuf> {
+    buf: &'a mut B,
+    truncate: bool,
+    full: bool,
+}
+impl<'a, B: FixedBuf> Writer<'a, B> {
+    // without `truncate` nothing more is written once a piece does not fit,
+    // with it as many chars of that piece as fit are written first
+    pub(crate) fn new(buf: &'a mut B, truncate: bool) -> Self {
+        Writer { buf, truncate, full: false }
+    }
+
+    fn put(&mut self, s: &str) -> Result<(), Full> {
+        if self.full {
+            return Err(Full);
+        }
+        if self.buf.try_push_str(s) {
+            return Ok(());
+        }
+        self.full = true;
+        if self.truncate {
+            let mut tmp = [0u8; 4];
+            for c in s.chars() {
+                if !self.buf.try_push_str(c.encode_utf8(&mut tmp)) {
+                    break;
+                }
+            }
+        }
+        Err(Full)
+    }
+
+    // `s` goes in whole or not at all, even when truncating
+    fn put_whole(&mut self, s: &str) -> Result<(), Full> {
+        if self.full {
+            return Err(Full);
+        }
+        if self.buf.try_push_str(s) {
+            return Ok(());
+        }
+        self.full = true;
+        Err(Full)
+    }
+}
+impl<B: FixedBuf> Write for Writer<'_, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.put(s).map_err(|_| fmt::Error)
+    }
+}
+
+// sources which can be written into a `Writer`
+pub(crate) trait LossyPieces {
+    fn write_pieces<B: FixedBuf>(self, w: &mut Writer<'_, B>) -> Result<(), Full>;
+}
+
+impl LossyPieces for &str {
+    fn write_pieces<B: FixedBuf>(self, w: &mut Writer<'_, B>) -> Result<(), Full> {
+        w.put(self)
+    }
+}
+
+// same as `String::from_utf8_lossy`
+impl LossyPieces for &[u8] {
+    fn write_pieces<B: FixedBuf>(self, w: &mut Writer<'_, B>) -> Result<(), Full> {
+        count_conversion();
+        if let Some(s) = local_validate(self) {
+            return w.put(s);
+        }
+        lossy("[u8]", self, invalid_runs(self));
+        for chunk in self.utf8_chunks() {
+            w.put(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                w.put("\u{FFFD}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// same as `into_string`, a `U+FFFD` per byte when it is not utf8
+impl LossyPieces for &CStr {
+    fn write_pieces<B: FixedBuf>(self, w: &mut Writer<'_, B>) -> Result<(), Full> {
+        let bytes = self.to_bytes();
+        count_conversion();
+        if let Some(s) = local_validate(bytes) {
+            return w.put(s);
+        }
+        lossy("CStr", bytes, bytes.len());
+        for _ in bytes {
+            w.put("\u{FFFD}")?;
+        }
+        Ok(())
+    }
+}
+
+impl LossyPieces for char {
+    fn write_pieces<B: FixedBuf>(self, w: &mut Writer<'_, B>) -> Result<(), Full> {
+        w.put(self.encode_utf8(&mut [0u8; 4]))
+    }
+}
+
+int_pieces!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// an integer written out on the stack, so it can go into the output in
+// one piece (`i128::MIN` is the longest, 40 bytes)
+struct Digits {
+    buf: [u8; 40],
+    len: usize,
+}
+impl Digits {
+    fn new() -> Self {
+        Digits { buf: [0; 40], len: 0 }
+    }
+
+    fn as_str(&self) -> &str {
+        // SAFETY: only ever written from `&str`, which is cut at the end
+        // of one
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+}
+impl Write for Digits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
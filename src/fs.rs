@@ -0,0 +1,325 @@
+//! Lossy versions of the string returning functions in
+//! [`std::fs`](https://doc.rust-lang.org/std/fs/index.html).
+
+use std::{
+    ffi::OsString,
+    fs::{self,DirEntry,FileType,Metadata,Permissions},
+    io,
+    path::Path,
+};
+
+use super::{IntoString,IntoStringBase,IntoStringNoBom,local_vec_to_str,os::path_buf_to_str,sealed::Sealed};
+
+/// Reads the entire contents of a file into a `String`.
+///
+/// Unlike [`std::fs::read_to_string`](https://doc.rust-lang.org/std/fs/fn.read_to_string.html)
+/// this will not fail on invalid utf8, bad sequences are replaced
+/// with `U+FFFD`. When the file is valid utf8 the buffer read from
+/// the file is returned as is, without a second copy.
+///
+/// IO errors are still returned as `Err`.
+pub fn read_to_string_lossy<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fs::read(path).map(|x| local_vec_to_str("File", x))
+}
+
+/// Same as [`read_to_string_lossy`], without a leading utf8 byte order
+/// mark, see [`bytes_into_string_no_bom`](crate::bytes_into_string_no_bom).
+pub fn read_to_string_lossy_no_bom<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fs::read(path).map(IntoStringNoBom::into_string_no_bom)
+}
+
+/// Returns the absolute path of `path` with every symlink resolved, as a
+/// `String`.
+///
+/// See [`std::fs::canonicalize`](https://doc.rust-lang.org/std/fs/fn.canonicalize.html),
+/// `path` has to exist. The buffer of the `PathBuf` is kept when it is
+/// valid utf8. On Windows the result starts with `\\?\`, see
+/// [`strip_verbatim_prefix`](crate::path::strip_verbatim_prefix) to show
+/// it to a user.
+pub fn canonicalize_lossy<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fs::canonicalize(path).map(path_buf_to_str)
+}
+
+/// Lists the bare file names in `dir`, converted lossily.
+///
+/// The names are in the order the platform returns them, which is not
+/// sorted. An error reading any entry (not just opening `dir`) is returned
+/// as the `Err`, nothing is skipped.
+pub fn read_dir_names_lossy<P: AsRef<Path>>(dir: P) -> io::Result<Vec<String>> {
+    fs::read_dir(dir)?
+        .map(|entry| entry.map(|x| x.file_name_string()))
+        .collect()
+}
+
+/// Same as [`read_dir_names_lossy`] with the full path of every entry
+/// (`dir` joined with the name) instead of the bare name
+pub fn read_dir_paths_lossy<P: AsRef<Path>>(dir: P) -> io::Result<Vec<String>> {
+    fs::read_dir(dir)?
+        .map(|entry| entry.map(|x| x.path_string()))
+        .collect()
+}
+
+/// Extension trait for [`DirEntry`](https://doc.rust-lang.org/std/fs/struct.DirEntry.html)
+///
+/// `DirEntry` only hands out owned values (`file_name` returns an `OsString`),
+/// so there is no zero-copy `Cow` version of these. Instead the owned buffer
+/// is given to the `String` when it is valid utf8, so valid names cost
+/// exactly one allocation (the one std already makes).
+pub trait DirEntryExt {
+    /// The bare file name of the entry, see `DirEntry::file_name`
+    fn file_name_string(&self) -> String;
+
+    /// The full path of the entry, see `DirEntry::path`
+    fn path_string(&self) -> String;
+}
+
+impl DirEntryExt for DirEntry {
+    fn file_name_string(&self) -> String {
+        <OsString as IntoString>::into_string(self.file_name())
+    }
+    fn path_string(&self) -> String {
+        path_buf_to_str(self.path())
+    }
+}
+
+impl IntoString for FileType {
+    /// `file`, `directory`, `symlink`, or `other` for everything else
+    /// (sockets, devices and the like)
+    fn into_string(self) -> String {
+        file_type_str(&self).to_string()
+    }
+}
+impl Sealed for FileType {}
+impl IntoStringBase for FileType {
+    fn ref_into_string(&self) -> String {
+        file_type_str(self).to_string()
+    }
+}
+
+impl IntoString for Permissions {
+    /// `readonly` or `writable`, followed by the octal mode on Unix,
+    /// `writable (0o755)`
+    fn into_string(self) -> String {
+        permissions_string(&self)
+    }
+}
+impl Sealed for Permissions {}
+impl IntoStringBase for Permissions {
+    fn ref_into_string(&self) -> String {
+        permissions_string(self)
+    }
+}
+
+/// One line describing `metadata`, the file type, length and permissions
+/// as their `IntoString` impls write them.
+///
+/// `file, 1024 bytes, writable (0o644)` on Unix, `directory, 0 bytes, writable`
+/// elsewhere.
+pub fn metadata_summary_string(metadata: &Metadata) -> String {
+    format!(
+        "{}, {} bytes, {}",
+        file_type_str(&metadata.file_type()),
+        metadata.len(),
+        permissions_string(&metadata.permissions()),
+    )
+}
+
+fn file_type_str(file_type: &FileType) -> &'static str {
+    if file_type.is_file() {
+        "file"
+    } else if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_symlink() {
+        "symlink"
+    } else {
+        "other"
+    }
+}
+
+fn permissions_string(permissions: &Permissions) -> String {
+    let access = if permissions.readonly() { "readonly" } else { "writable" };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        format!("{} ({:#o})", access, permissions.mode() & 0o7777)
+    }
+    #[cfg(not(unix))]
+    access.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    // a new empty directory for one test, named after it
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("to_string-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn read_valid_invalid_and_empty_files() {
+        let dir = test_dir("read");
+        fs::write(dir.join("valid"), "héllo").unwrap();
+        fs::write(dir.join("invalid"), b"a\xFFb").unwrap();
+        fs::write(dir.join("empty"), b"").unwrap();
+        assert_eq!(read_to_string_lossy(dir.join("valid")).unwrap(), "héllo");
+        assert_eq!(read_to_string_lossy(dir.join("invalid")).unwrap(), "a\u{FFFD}b");
+        assert_eq!(read_to_string_lossy(dir.join("empty")).unwrap(), "");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_without_a_bom() {
+        let dir = test_dir("bom");
+        fs::write(dir.join("bom"), b"\xEF\xBB\xBFh\xC3\xA9llo\xEF\xBB\xBF").unwrap();
+        assert_eq!(read_to_string_lossy_no_bom(dir.join("bom")).unwrap(), "héllo\u{FEFF}");
+        assert_eq!(read_to_string_lossy(dir.join("bom")).unwrap(), "\u{FEFF}héllo\u{FEFF}");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_missing_file_is_an_error() {
+        let dir = test_dir("missing");
+        let err = read_to_string_lossy(dir.join("nope")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // the entries of `dir`, sorted by name
+    fn entries(dir: &Path) -> Vec<DirEntry> {
+        let mut entries: Vec<DirEntry> = fs::read_dir(dir).unwrap().map(Result::unwrap).collect();
+        entries.sort_by_key(DirEntry::file_name);
+        entries
+    }
+
+    #[test]
+    fn dir_entry_strings() {
+        let dir = test_dir("entry");
+        fs::write(dir.join("plain.txt"), b"").unwrap();
+        let entries = entries(&dir);
+        assert_eq!(entries[0].file_name_string(), "plain.txt");
+        assert_eq!(entries[0].path_string(), dir.join("plain.txt").to_str().unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dir_entry_invalid_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = test_dir("entry-invalid");
+        fs::write(dir.join(std::ffi::OsStr::from_bytes(b"bad\xFF.txt")), b"").unwrap();
+        let entries = entries(&dir);
+        assert_eq!(entries[0].file_name_string(), "bad\u{FFFD}.txt");
+        assert!(entries[0].path_string().ends_with("/bad\u{FFFD}.txt"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_types() {
+        let dir = test_dir("types");
+        fs::write(dir.join("file"), b"").unwrap();
+        assert_eq!(fs::metadata(dir.join("file")).unwrap().file_type().into_string(), "file");
+        assert_eq!((&fs::metadata(&dir).unwrap().file_type()).into_string(), "directory");
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("file"), dir.join("link")).unwrap();
+            assert_eq!(fs::symlink_metadata(dir.join("link")).unwrap().file_type().into_string(), "symlink");
+            // the target, not the link
+            assert_eq!(fs::metadata(dir.join("link")).unwrap().file_type().into_string(), "file");
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn readonly_and_writable() {
+        let dir = test_dir("readonly");
+        let file = dir.join("file");
+        fs::write(&file, b"1234").unwrap();
+        let mut permissions = fs::metadata(&file).unwrap().permissions();
+        assert!(permissions.clone().into_string().starts_with("writable"));
+        permissions.set_readonly(true);
+        assert!(permissions.into_string().starts_with("readonly"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = test_dir("modes");
+        let file = dir.join("file");
+        fs::write(&file, b"1234").unwrap();
+        fs::set_permissions(&file, Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(fs::metadata(&file).unwrap().permissions().into_string(), "writable (0o755)");
+        assert_eq!(metadata_summary_string(&fs::metadata(&file).unwrap()), "file, 4 bytes, writable (0o755)");
+        fs::set_permissions(&file, Permissions::from_mode(0o444)).unwrap();
+        assert_eq!((&fs::metadata(&file).unwrap().permissions()).into_string(), "readonly (0o444)");
+        // the file type bits are left off
+        assert_eq!(Permissions::from_mode(0o100_640).into_string(), "writable (0o640)");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn read_dir_names_and_paths() {
+        let dir = test_dir("names");
+        fs::write(dir.join("b.txt"), b"").unwrap();
+        fs::create_dir(dir.join("a dir")).unwrap();
+        let mut names = read_dir_names_lossy(&dir).unwrap();
+        names.sort();
+        assert_eq!(names, ["a dir", "b.txt"]);
+        let mut paths = read_dir_paths_lossy(&dir).unwrap();
+        paths.sort();
+        assert_eq!(paths, [dir.join("a dir").to_str().unwrap(), dir.join("b.txt").to_str().unwrap()]);
+        assert_eq!(read_dir_names_lossy(dir.join("missing")).unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert!(read_dir_paths_lossy(dir.join("b.txt")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_dir_invalid_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = test_dir("names-invalid");
+        fs::write(dir.join(std::ffi::OsStr::from_bytes(b"n\xFFme")), b"").unwrap();
+        assert_eq!(read_dir_names_lossy(&dir).unwrap(), ["n\u{FFFD}me"]);
+        let paths = read_dir_paths_lossy(&dir).unwrap();
+        assert_eq!(paths, [format!("{}/n\u{FFFD}me", dir.to_str().unwrap())]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn canonicalize_relative_and_dangling() {
+        // tests run in the crate directory
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let expected = fs::canonicalize(&manifest).unwrap();
+        assert_eq!(canonicalize_lossy("Cargo.toml").unwrap(), expected.to_str().unwrap());
+        assert_eq!(canonicalize_lossy("src/../Cargo.toml").unwrap(), expected.to_str().unwrap());
+        let dir = test_dir("canonical");
+        let err = canonicalize_lossy(dir.join("dangling")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn canonicalize_resolves_symlinks() {
+        let dir = test_dir("canonical-link");
+        fs::write(dir.join("target"), b"").unwrap();
+        std::os::unix::fs::symlink(dir.join("target"), dir.join("link")).unwrap();
+        let target = fs::canonicalize(dir.join("target")).unwrap();
+        assert_eq!(canonicalize_lossy(dir.join("link")).unwrap(), target.to_str().unwrap());
+        // the link is there, what it points to is not
+        fs::remove_file(dir.join("target")).unwrap();
+        assert!(canonicalize_lossy(dir.join("link")).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
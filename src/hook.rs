@@ -0,0 +1,205 @@
+//! Finding out when a conversion had to replace something.
+//!
+//! A hook is handed a [`LossyEvent`] for every bad sequence in the input of
+//! a conversion. They fire for everything which starts out as utf8 bytes:
+//! the std types (`OsStr`, `Path`, `CStr` and the like), `c_char` arrays,
+//! the byte types from other crates (`BString`, `Bytes`, ...), readers, a
+//! [`LossyDecoder`](crate::LossyDecoder) and
+//! [`Converter::convert_bytes`](crate::Converter::convert_bytes). UTF-16
+//! and UTF-32 input does not report anything.
+//!
+//! Nothing is checked while the input is valid, a conversion which does
+//! replace something checks the global hook (an atomic load) and the hook
+//! of the `Converter` it runs in, if any.
+
+use std::{
+    cell::{Cell,RefCell},
+    fmt,
+    sync::{Arc,OnceLock},
+};
+
+/// A bad sequence found by a conversion
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct LossyEvent<'a> {
+    /// The type which was converted, `"OsString"`, `"CStr"`, ...
+    pub source: &'static str,
+    /// Where `invalid` starts in the input, in bytes. For `OsStr` and the
+    /// like this is the raw representation, WTF-8 on Windows. A BOM which
+    /// is stripped ([`Converter::strip_bom`](crate::Converter::strip_bom),
+    /// [`bytes_into_string_no_bom`](crate::bytes_into_string_no_bom)) is
+    /// counted, the offset is into the bytes as they were passed in.
+    pub invalid_offset: usize,
+    /// The bad bytes, what they became follows the rules of `source`
+    /// (`CStr` replaces the whole input, not just this)
+    pub invalid: &'a [u8],
+}
+
+static GLOBAL: OnceLock<fn(&LossyEvent)> = OnceLock::new();
+
+thread_local! {
+    // the hook of the `Converter::convert` which is running, if any
+    static SCOPED: RefCell<Option<LossyHook>> = const { RefCell::new(None) };
+    // lossy conversions so far on this thread, for `was_lossy`
+    static LOSSY_SEEN: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Installs `hook` for every conversion in the process.
+///
+/// There is only one, and it can not be replaced once it is set, the
+/// hook which is already installed is returned as the `Err`.
+pub fn set_lossy_hook(hook: fn(&LossyEvent)) -> Result<(), fn(&LossyEvent)> {
+    GLOBAL.set(hook)
+}
+
+/// The closure given to [`Converter::on_lossy`](crate::Converter::on_lossy)
+#[derive(Clone)]
+pub(crate) struct LossyHook(pub(crate) Arc<dyn Fn(&LossyEvent) + Send + Sync>);
+
+impl fmt::Debug for LossyHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LossyHook")
+    }
+}
+
+// runs `f` with `hook` as the scoped hook, the previous one is put back
+// afterwards (even when `f` panics) so nested converters work
+pub(crate) fn with_scoped<R>(hook: &LossyHook, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<LossyHook>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            let _ = SCOPED.try_with(|s| *s.borrow_mut() = previous);
+        }
+    }
+    let previous = SCOPED.with(|s| s.borrow_mut().replace(hook.clone()));
+    let _restore = Restore(previous);
+    f()
+}
+
+// runs `f`, and says whether any conversion in it was lossy
+pub(crate) fn was_lossy<R>(f: impl FnOnce() -> R) -> (R, bool) {
+    let before = LOSSY_SEEN.with(Cell::get);
+    let out = f();
+    (out, LOSSY_SEEN.with(Cell::get) != before)
+}
+
+// called by a conversion which found `bytes` are not utf8, one event per
+// bad sequence
+pub(crate) fn report_lossy(source: &'static str, bytes: &[u8]) {
+    let _ = LOSSY_SEEN.try_with(|x| x.set(x.get().wrapping_add(1)));
+    let global = GLOBAL.get();
+    let scoped = SCOPED.try_with(|s| s.borrow().clone()).ok().flatten();
+    if global.is_none() && scoped.is_none() {
+        return;
+    }
+    let mut offset = 0;
+    for chunk in bytes.utf8_chunks() {
+        offset += chunk.valid().len();
+        let invalid = chunk.invalid();
+        if !invalid.is_empty() {
+            let event = LossyEvent { source, invalid_offset: offset, invalid };
+            if let Some(hook) = global {
+                hook(&event);
+            }
+            if let Some(hook) = &scoped {
+                (hook.0)(&event);
+            }
+        }
+        offset += invalid.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{Converter,IntoString};
+
+    // what a hook saw, as owned values
+    type Seen = Arc<Mutex<Vec<(&'static str, usize, Vec<u8>)>>>;
+
+    fn recording() -> (Converter, Seen) {
+        let seen = Seen::default();
+        let record = seen.clone();
+        let converter = Converter::new().on_lossy(move |e| {
+            record.lock().unwrap().push((e.source, e.invalid_offset, e.invalid.to_vec()));
+        });
+        (converter, seen)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_os_string() {
+        use std::{ffi::OsString,os::unix::ffi::OsStringExt};
+
+        let (converter, seen) = recording();
+        let name = OsString::from_vec(b"ab\xFFcd\xE2\x82".to_vec());
+        assert_eq!(converter.convert(name), "ab\u{FFFD}cd\u{FFFD}");
+        assert_eq!(*seen.lock().unwrap(), [("OsString", 2, vec![0xFF]), ("OsString", 5, vec![0xE2, 0x82])]);
+    }
+
+    #[test]
+    fn valid_input_fires_nothing() {
+        let (converter, seen) = recording();
+        assert_eq!(converter.convert(c"valid"), "valid");
+        assert_eq!(converter.convert_bytes("h\u{e9}llo".as_bytes()), "h\u{e9}llo");
+        assert!(seen.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_inside_the_converter() {
+        let (converter, seen) = recording();
+        assert_eq!(c"\xFF".into_string(), "\u{FFFD}");
+        assert!(seen.lock().unwrap().is_empty());
+        assert_eq!(converter.convert_bytes(b"a\xFF"), "a\u{FFFD}");
+        assert_eq!(*seen.lock().unwrap(), [("[u8]", 1, vec![0xFF])]);
+    }
+
+    #[test]
+    fn offset_counts_a_stripped_bom() {
+        let (converter, seen) = recording();
+        let converter = converter.strip_bom(true);
+        assert_eq!(converter.convert_bytes(b"\xEF\xBB\xBFa\xFF"), "a\u{FFFD}");
+        assert_eq!(*seen.lock().unwrap(), [("[u8]", 4, vec![0xFF])]);
+    }
+
+    // converts with `inner` while it is displayed
+    struct Nested(Converter);
+
+    impl fmt::Display for Nested {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0.convert_bytes(b"\xFF"))
+        }
+    }
+
+    #[test]
+    fn nested_converters() {
+        let (outer, outer_seen) = recording();
+        let (inner, inner_seen) = recording();
+        assert_eq!(outer.convert(crate::ViaDisplay(Nested(inner))), "\u{FFFD}");
+        // the outer hook is back once the inner converter is done
+        assert_eq!(outer.convert_bytes(b"\xFE"), "\u{FFFD}");
+        assert_eq!(*inner_seen.lock().unwrap(), [("[u8]", 0, vec![0xFF])]);
+        assert_eq!(*outer_seen.lock().unwrap(), [("[u8]", 0, vec![0xFE])]);
+    }
+
+    thread_local! {
+        static GLOBAL_SEEN: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // the global hook is there for the rest of the process, it only
+    // records what happens on the thread which calls it
+    #[test]
+    fn global_hook() {
+        fn record(e: &LossyEvent) {
+            GLOBAL_SEEN.with(|x| x.borrow_mut().push(e.invalid_offset));
+        }
+        set_lossy_hook(record).unwrap();
+        assert!(set_lossy_hook(record).is_err());
+        assert_eq!(Converter::new().convert_bytes(b"valid"), "valid");
+        assert!(GLOBAL_SEEN.with(|x| x.borrow().is_empty()));
+        assert_eq!(Converter::new().convert_bytes(b"abc\xFF"), "abc\u{FFFD}");
+        assert_eq!(GLOBAL_SEEN.with(|x| x.borrow().clone()), [3]);
+    }
+}
@@ -0,0 +1,59 @@
+//! `anyhow::Error` with its context chain, in the single line
+//! [`Style`](crate::error::Style) of [`error_chain_string`](crate::error::error_chain_string).
+
+use crate::{
+    IntoString,IntoStringBase,
+    sealed::Sealed,
+    error::{Style,chain_string},
+};
+
+impl IntoString for anyhow::Error {
+    fn into_string(self) -> String {
+        anyhow_chain_string(&self, Style::SingleLine)
+    }
+}
+impl Sealed for anyhow::Error {}
+impl IntoStringBase for anyhow::Error {
+    fn ref_into_string(&self) -> String {
+        anyhow_chain_string(self, Style::SingleLine)
+    }
+}
+
+/// Renders `err` and its context chain in `style`, see
+/// [`error_chain_string`](crate::error::error_chain_string)
+pub fn anyhow_chain_string(err: &anyhow::Error, style: Style) -> String {
+    chain_string(err.chain(), style)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context,anyhow};
+
+    use super::*;
+
+    fn chained() -> anyhow::Error {
+        Err::<(), _>(anyhow!("file not found"))
+            .context("reading config")
+            .context("starting server")
+            .unwrap_err()
+    }
+
+    #[test]
+    fn single_line() {
+        let err = chained();
+        assert_eq!((&err).into_string(), "starting server: reading config: file not found");
+        assert_eq!(anyhow_chain_string(&err, Style::SingleLine), format!("{:#}", err));
+        assert_eq!(err.into_string(), "starting server: reading config: file not found");
+    }
+
+    #[test]
+    fn multi_line() {
+        assert_eq!(
+            anyhow_chain_string(&chained(), Style::MultiLine),
+            "starting server\n\nCaused by:\n    0: reading config\n    1: file not found",
+        );
+        let one = anyhow!("file not found").context("reading config");
+        assert_eq!(anyhow_chain_string(&one, Style::MultiLine), "reading config\n\nCaused by:\n    file not found");
+        assert_eq!(anyhow_chain_string(&anyhow!("alone"), Style::MultiLine), "alone");
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    ffi::{CString,OsString},
+    path::PathBuf,
+};
+
+use arcstr::ArcStr;
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,AsLossyStr};
+
+impl IntoString for ArcStr {
+    /// The text is shared with other `ArcStr`s so it is always copied,
+    /// there is no buffer to hand over.
+    fn into_string(self) -> String {
+        self.as_str().to_string()
+    }
+}
+impl Sealed for ArcStr {}
+impl IntoStringBase for ArcStr {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// Converts _something_ into an
+/// [`ArcStr`](https://docs.rs/arcstr/latest/arcstr/struct.ArcStr.html)
+///
+/// The `ArcStr` is built from the final text in one step, so valid
+/// sources are copied once and never go through an intermediate `String`.
+pub trait IntoArcStr {
+    fn into_arc_str(self) -> ArcStr;
+}
+
+impl<T: AsLossyStr + ?Sized> IntoArcStr for &T {
+    fn into_arc_str(self) -> ArcStr {
+        ArcStr::from(&*self.as_lossy_str())
+    }
+}
+impl IntoArcStr for String {
+    fn into_arc_str(self) -> ArcStr {
+        ArcStr::from(self.as_str())
+    }
+}
+impl IntoArcStr for OsString {
+    fn into_arc_str(self) -> ArcStr {
+        ArcStr::from(&*self.as_lossy_str())
+    }
+}
+impl IntoArcStr for CString {
+    fn into_arc_str(self) -> ArcStr {
+        ArcStr::from(&*self.as_lossy_str())
+    }
+}
+impl IntoArcStr for PathBuf {
+    fn into_arc_str(self) -> ArcStr {
+        ArcStr::from(&*self.as_lossy_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let shared = ArcStr::from("shared text");
+        let text = (&shared).into_string();
+        assert_eq!(text, "shared text");
+        assert_eq!(text.into_arc_str(), shared);
+        assert_eq!(OsStr::new("os").into_arc_str(), "os");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_sources() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let name = OsString::from_vec(b"bad\xFFname".to_vec());
+        assert_eq!((&name).into_arc_str(), "bad\u{FFFD}name");
+        assert_eq!(name.into_arc_str(), "bad\u{FFFD}name");
+        let c = c"\xFFx";
+        assert_eq!(c.into_arc_str(), "\u{FFFD}\u{FFFD}");
+        assert_eq!(CString::from(c).into_arc_str(), "\u{FFFD}\u{FFFD}");
+    }
+}
@@ -0,0 +1,109 @@
+//! Output into `arrayvec::ArrayString`, for code which must not allocate.
+
+use core::{error::Error,fmt};
+
+use arrayvec::ArrayString;
+
+use crate::fixed::{FixedBuf,LossyPieces,Writer};
+
+/// Converts into an `ArrayString<N>` with the same lossy rules as
+/// `into_string`, without allocating.
+///
+/// Implemented for `&str`, `&CStr`, `&[u8]` (replaced like
+/// `String::from_utf8_lossy`), `char` and the integer types. An integer
+/// is never cut part way through its digits, when it does not fit the
+/// truncating conversion leaves it out entirely (with `ellipsis` only
+/// the `…` is left).
+pub trait IntoArrayString: Sized {
+    /// Fails when the result is longer than `N` bytes
+    fn into_array_string<const N: usize>(self) -> Result<ArrayString<N>, Overflow>;
+
+    /// Keeps as much as fits in `N` bytes, cut at a char boundary.
+    ///
+    /// With `ellipsis` a cut string ends in `…` (3 bytes), when `N`
+    /// is large enough for it. Strings which fit are never changed.
+    fn into_array_string_truncating<const N: usize>(self, ellipsis: bool) -> ArrayString<N>;
+}
+
+impl<T: LossyPieces> IntoArrayString for T {
+    fn into_array_string<const N: usize>(self) -> Result<ArrayString<N>, Overflow> {
+        let mut out = ArrayString::new();
+        match self.write_pieces(&mut Writer::new(&mut out, false)) {
+            Ok(()) => Ok(out),
+            Err(_) => Err(Overflow),
+        }
+    }
+    fn into_array_string_truncating<const N: usize>(self, ellipsis: bool) -> ArrayString<N> {
+        const ELLIPSIS: &str = "\u{2026}";
+        let mut out = ArrayString::new();
+        let cut = self.write_pieces(&mut Writer::new(&mut out, true)).is_err();
+        if cut && ellipsis && N >= ELLIPSIS.len() {
+            while out.len() + ELLIPSIS.len() > N {
+                out.pop();
+            }
+            out.push_str(ELLIPSIS);
+        }
+        out
+    }
+}
+
+impl<const N: usize> FixedBuf for ArrayString<N> {
+    fn try_push_str(&mut self, s: &str) -> bool {
+        ArrayString::try_push_str(self, s).is_ok()
+    }
+}
+
+/// The result did not fit in the `ArrayString`, see [`IntoArrayString`]
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("string does not fit in the ArrayString")
+    }
+}
+
+impl Error for Overflow { }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_fit() {
+        assert_eq!("héll".into_array_string::<5>().unwrap().as_str(), "héll");
+        assert_eq!("héll".into_array_string::<4>(), Err(Overflow));
+        assert_eq!("".into_array_string::<0>().unwrap().as_str(), "");
+    }
+
+    #[test]
+    fn truncated_through_a_replacement() {
+        let bytes: &[u8] = b"ab\xFFcd";
+        assert_eq!(bytes.into_array_string::<8>().unwrap().as_str(), "ab\u{FFFD}cd");
+        // the `U+FFFD` is 3 bytes, only 2 of them fit
+        assert_eq!(bytes.into_array_string_truncating::<4>(false).as_str(), "ab");
+        assert_eq!(bytes.into_array_string_truncating::<5>(false).as_str(), "ab\u{FFFD}");
+        assert_eq!(c"a\xFF".into_array_string_truncating::<4>(false).as_str(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn ellipsis_at_the_boundary() {
+        // fits, the ellipsis is not added
+        assert_eq!("abcdef".into_array_string_truncating::<6>(true).as_str(), "abcdef");
+        // one byte over, the `…` takes the place of the last 3
+        assert_eq!("abcdefg".into_array_string_truncating::<6>(true).as_str(), "abc…");
+        // the `…` replaces whole chars
+        assert_eq!("aéé".into_array_string_truncating::<4>(true).as_str(), "a…");
+        // no room for the `…`
+        assert_eq!("abcdef".into_array_string_truncating::<2>(true).as_str(), "ab");
+        assert_eq!("abcd".into_array_string_truncating::<3>(true).as_str(), "…");
+    }
+
+    #[test]
+    fn integers_are_never_split() {
+        assert_eq!(u64::MAX.into_array_string::<20>().unwrap().as_str(), "18446744073709551615");
+        assert_eq!(12345u32.into_array_string_truncating::<4>(false).as_str(), "");
+        assert_eq!(12345u32.into_array_string_truncating::<4>(true).as_str(), "…");
+        assert_eq!((-5i8).into_array_string::<1>(), Err(Overflow));
+    }
+}
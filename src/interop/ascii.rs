@@ -0,0 +1,78 @@
+use ascii::{AsciiChar,AsciiStr,AsciiString};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for AsciiString {
+    /// Special case, ascii is always utf8 so the buffer is handed
+    /// over without being checked again
+    fn into_string(self) -> String {
+        String::from(self)
+    }
+}
+impl Sealed for AsciiString {}
+impl IntoStringBase for AsciiString {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl Sealed for AsciiStr {}
+impl IntoStringBase for AsciiStr {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// Converts _something_ into an
+/// [`AsciiString`](https://docs.rs/ascii/latest/ascii/struct.AsciiString.html)
+///
+/// Implemented for everything which implements `IntoString`. After the
+/// normal lossy conversion every character which is not ascii (including
+/// the `U+FFFD` from bad sequences) becomes `substitute`.
+pub trait IntoAsciiStringLossy {
+    fn into_ascii_string_lossy(self, substitute: AsciiChar) -> AsciiString;
+}
+
+impl<T: IntoString> IntoAsciiStringLossy for T {
+    fn into_ascii_string_lossy(self, substitute: AsciiChar) -> AsciiString {
+        let text = self.into_string();
+        let mut out = AsciiString::with_capacity(text.len());
+        for c in text.chars() {
+            out.push(AsciiChar::from_ascii(c).unwrap_or(substitute));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_keeps_the_buffer() {
+        let ascii = AsciiString::from_ascii("GET /index.html").unwrap();
+        let ptr = ascii.as_str().as_ptr();
+        let text = IntoString::into_string(ascii);
+        assert_eq!(text, "GET /index.html");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn borrowed() {
+        let ascii = AsciiString::from_ascii("Host").unwrap();
+        assert_eq!((&ascii).into_string(), "Host");
+        assert_eq!(AsciiStr::from_ascii("Host").unwrap().into_string(), "Host");
+        assert_eq!(AsciiString::new().into_string(), "");
+    }
+
+    #[test]
+    fn substitute() {
+        assert_eq!("caf\u{e9} 😀".into_ascii_string_lossy(AsciiChar::Question), "caf? ?");
+        assert_eq!("plain".into_ascii_string_lossy(AsciiChar::Question), "plain");
+        // the `U+FFFD` from a bad sequence is not ascii either
+        let c = std::ffi::CString::new(b"a\xFF".to_vec()).unwrap();
+        assert_eq!(c.into_ascii_string_lossy(AsciiChar::Asterisk), "**");
+        let name = std::ffi::OsString::from("na\u{EF}ve");
+        assert_eq!(name.into_ascii_string_lossy(AsciiChar::UnderScore), "na_ve");
+    }
+}
@@ -0,0 +1,65 @@
+//! `beef::Cow` and `beef::lean::Cow` over `str`. An owned one gives
+//! its buffer to the `String`, a borrowed one is copied.
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for beef::Cow<'_,str> {
+    fn into_string(self) -> String {
+        self.into_owned()
+    }
+}
+impl Sealed for beef::Cow<'_,str> {}
+impl IntoStringBase for beef::Cow<'_,str> {
+    fn ref_into_string(&self) -> String {
+        String::from(&**self)
+    }
+}
+
+// off 64 bit targets `lean::Cow` is the same type as `beef::Cow`
+#[cfg(target_pointer_width = "64")]
+impl IntoString for beef::lean::Cow<'_,str> {
+    fn into_string(self) -> String {
+        self.into_owned()
+    }
+}
+#[cfg(target_pointer_width = "64")]
+impl Sealed for beef::lean::Cow<'_,str> {}
+#[cfg(target_pointer_width = "64")]
+impl IntoStringBase for beef::lean::Cow<'_,str> {
+    fn ref_into_string(&self) -> String {
+        String::from(&**self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_keeps_the_buffer() {
+        let text = String::from("owned text");
+        let ptr = text.as_ptr();
+        let out = beef::Cow::<str>::owned(text).into_string();
+        assert_eq!(out, "owned text");
+        assert_eq!(out.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn borrowed() {
+        let cow = beef::Cow::borrowed("borrowed");
+        assert_eq!((&cow).into_string(), "borrowed");
+        assert_eq!(cow.into_string(), "borrowed");
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn lean() {
+        let text = String::from("lean");
+        let ptr = text.as_ptr();
+        let out = beef::lean::Cow::<str>::owned(text).into_string();
+        assert_eq!(out.as_ptr(), ptr);
+        let cow = beef::lean::Cow::borrowed("lean");
+        assert_eq!((&cow).into_string(), "lean");
+        assert_eq!(cow.into_string(), "lean");
+    }
+}
@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use bstr::{BStr,BString};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_to_cow,local_vec_to_str};
+
+impl IntoString for BString {
+    /// Special Case
+    ///
+    /// The underlying `Vec<u8>` is kept when it is valid utf8
+    fn into_string(self) -> String {
+        local_vec_to_str("BString", Vec::from(self))
+    }
+}
+impl Sealed for BString {}
+impl IntoStringBase for BString {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("BString", self.as_slice()).into_owned()
+    }
+}
+
+impl Sealed for BStr {}
+impl IntoStringBase for BStr {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("BStr", self).into_owned()
+    }
+}
+
+impl IntoString for Cow<'_,BStr> {
+    /// Special case.
+    ///
+    /// An owned `BString` is handled the same as `BString::into_string`
+    fn into_string(self) -> String {
+        match self {
+            Cow::Owned(x) => <BString as IntoString>::into_string(x),
+            Cow::Borrowed(x) => x.into_string(),
+        }
+    }
+}
+impl Sealed for Cow<'_,BStr> {}
+impl IntoStringBase for Cow<'_,BStr> {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("Cow<BStr>", self).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bstr::ByteSlice;
+
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn same_as_to_str_lossy() {
+        for bytes in TEST_BYTES {
+            let expected = bytes.to_str_lossy().into_owned();
+            assert_eq!(BString::from(*bytes).into_string(), expected);
+            assert_eq!((&BString::from(*bytes)).into_string(), expected);
+            assert_eq!(BStr::new(bytes).into_string(), expected);
+            assert_eq!(Cow::Borrowed(BStr::new(bytes)).into_string(), expected);
+            assert_eq!(Cow::<BStr>::Owned(BString::from(*bytes)).into_string(), expected);
+        }
+    }
+
+    #[test]
+    fn valid_bstring_keeps_its_buffer() {
+        let text = BString::from("valid");
+        let ptr = text.as_ptr();
+        let text = text.into_string();
+        assert_eq!(text.as_ptr(), ptr);
+    }
+}
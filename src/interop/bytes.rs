@@ -0,0 +1,86 @@
+use bytes::{Buf,Bytes,BytesMut};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,LossyDecoder,local_to_cow,local_vec_to_str};
+
+impl IntoString for Bytes {
+    /// Special Case
+    ///
+    /// When the `Bytes` is the only handle to its buffer the memory
+    /// is handed over without a copy (when it is valid utf8).
+    fn into_string(self) -> String {
+        local_vec_to_str("Bytes", Vec::from(self))
+    }
+}
+impl Sealed for Bytes {}
+impl IntoStringBase for Bytes {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("Bytes", self).into_owned()
+    }
+}
+
+impl IntoString for BytesMut {
+    /// Special Case
+    ///
+    /// Same as `Bytes`, a uniquely owned buffer is handed over when valid.
+    fn into_string(self) -> String {
+        local_vec_to_str("BytesMut", Vec::from(self))
+    }
+}
+impl Sealed for BytesMut {}
+impl IntoStringBase for BytesMut {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("BytesMut", self).into_owned()
+    }
+}
+
+/// Converts everything remaining in a
+/// [`Buf`](https://docs.rs/bytes/latest/bytes/trait.Buf.html) into a `String`.
+///
+/// The chunks are converted one at a time rather than joined first, a
+/// sequence split between two chunks is still decoded correctly.
+pub fn buf_into_string<B: Buf>(mut buf: B) -> String {
+    let mut decoder = LossyDecoder::new();
+    let mut text = String::new();
+    while buf.has_remaining() {
+        let chunk = buf.chunk();
+        let len = chunk.len();
+        decoder.push_to(chunk, &mut text);
+        buf.advance(len);
+    }
+    decoder.finish_to(&mut text);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn same_as_from_utf8_lossy() {
+        for bytes in TEST_BYTES {
+            let expected = String::from_utf8_lossy(bytes);
+            assert_eq!(Bytes::copy_from_slice(bytes).into_string(), expected);
+            assert_eq!((&Bytes::copy_from_slice(bytes)).into_string(), expected);
+            assert_eq!(BytesMut::from(*bytes).into_string(), expected);
+            assert_eq!(buf_into_string(*bytes), expected);
+        }
+    }
+
+    #[test]
+    fn unique_bytes_mut_hands_over_its_buffer() {
+        let bytes = BytesMut::from("valid text");
+        let ptr = bytes.as_ptr();
+        let text = bytes.into_string();
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn sequence_split_between_chunks() {
+        let euro = "€".as_bytes();
+        let buf = Bytes::from_static(b"a").chain(&euro[..1]).chain(&euro[1..]).chain(&b"b"[..]);
+        assert_eq!(buf_into_string(buf), "a€b");
+        let cut = (&b"a\xE2\x82"[..]).chain(&b"b"[..]);
+        assert_eq!(buf_into_string(cut), "a\u{FFFD}b");
+    }
+}
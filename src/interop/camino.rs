@@ -0,0 +1,46 @@
+use camino::{Utf8Path,Utf8PathBuf};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for Utf8PathBuf {
+    /// Special case, the path is already a `String` so it is handed over
+    fn into_string(self) -> String {
+        Utf8PathBuf::into_string(self)
+    }
+}
+impl Sealed for Utf8PathBuf {}
+impl IntoStringBase for Utf8PathBuf {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl Sealed for Utf8Path {}
+impl IntoStringBase for Utf8Path {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_path_hands_over_its_buffer() {
+        let path = Utf8PathBuf::from("dir/file.txt");
+        let ptr = path.as_str().as_ptr();
+        let text = IntoString::into_string(path);
+        assert_eq!(text, "dir/file.txt");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn borrowed_paths() {
+        let path = Utf8PathBuf::from("dir/file.txt");
+        assert_eq!((&path).into_string(), "dir/file.txt");
+        assert_eq!(path.as_path().into_string(), "dir/file.txt");
+        #[cfg(feature = "deep-refs")]
+        assert_eq!((&&path.as_path()).into_string(), "dir/file.txt");
+    }
+}
@@ -0,0 +1,55 @@
+use chardetng::{EncodingDetector,Iso2022JpDetection,Utf8Detection};
+use encoding_rs::{Encoding,UTF_8};
+
+use crate::{local_to_cow,local_validate};
+
+/// Decodes `bytes` with a best guess at their encoding, returning the
+/// text along with the encoding that was used.
+///
+/// Valid utf8 is always taken as utf8. Otherwise
+/// [`chardetng`](https://docs.rs/chardetng) picks a (non utf8) legacy
+/// encoding and the bytes are decoded with it. If that guess still
+/// produces malformed sequences the guess is thrown away and the normal
+/// lossy utf8 conversion is used instead, reporting `UTF_8`.
+///
+/// This never fails. ISO-2022-JP is never guessed.
+pub fn bytes_into_string_detected(bytes: &[u8]) -> (String, &'static Encoding) {
+    if let Some(text) = local_validate(bytes) {
+        return (text.to_string(), UTF_8);
+    }
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+    detector.feed(bytes, true);
+    let guess = detector.guess(None, Utf8Detection::Deny);
+    match guess.decode(bytes) {
+        (text, used, false) => (text.into_owned(), used),
+        (_, _, true) => (local_to_cow("[u8]", bytes).into_owned(), UTF_8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{SHIFT_JIS,WINDOWS_1252};
+
+    use super::*;
+
+    #[test]
+    fn utf8_is_kept() {
+        let (text, used) = bytes_into_string_detected("déjà vu, 日本語".as_bytes());
+        assert_eq!(text, "déjà vu, 日本語");
+        assert_eq!(used, UTF_8);
+    }
+
+    #[test]
+    fn windows_1252_text() {
+        let text = "Le garçon a mangé une crème brûlée à côté du café, très été.";
+        let (bytes, _, _) = WINDOWS_1252.encode(text);
+        assert_eq!(bytes_into_string_detected(&bytes), (text.to_string(), WINDOWS_1252));
+    }
+
+    #[test]
+    fn shift_jis_text() {
+        let text = "これは日本語のテキストです。文字コードを自動で判定します。";
+        let (bytes, _, _) = SHIFT_JIS.encode(text);
+        assert_eq!(bytes_into_string_detected(&bytes), (text.to_string(), SHIFT_JIS));
+    }
+}
@@ -0,0 +1,85 @@
+//! `DateTime` is formatted as RFC 3339, the same as
+//! `to_rfc3339_opts(SecondsFormat::AutoSi, true)`:
+//!
+//! * the fractional seconds are left out when zero, otherwise they are
+//!   3, 6 or 9 digits, whichever is the shortest without losing precision
+//! * an offset of zero is written as `Z`, anything else as `+hh:mm`/`-hh:mm`
+//!
+//! So `2024-03-01T12:30:00Z`, `2024-03-01T12:30:00.250+05:30`.
+//!
+//! `NaiveDateTime` has no offset, it is written the same way with the
+//! offset left off, `2024-03-01T12:30:00.250`.
+
+use chrono::{DateTime,NaiveDateTime,SecondsFormat,TimeZone};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+const NAIVE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+impl<Tz: TimeZone> IntoString for DateTime<Tz> {
+    fn into_string(self) -> String {
+        self.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+impl<Tz: TimeZone> Sealed for DateTime<Tz> {}
+impl<Tz: TimeZone> IntoStringBase for DateTime<Tz> {
+    fn ref_into_string(&self) -> String {
+        self.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+impl IntoString for NaiveDateTime {
+    fn into_string(self) -> String {
+        self.format(NAIVE_FORMAT).to_string()
+    }
+}
+impl Sealed for NaiveDateTime {}
+impl IntoStringBase for NaiveDateTime {
+    fn ref_into_string(&self) -> String {
+        self.format(NAIVE_FORMAT).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset,NaiveDate,Utc};
+
+    use super::*;
+
+    #[test]
+    fn utc() {
+        let x = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+        assert_eq!(x.into_string(), "2024-03-01T12:30:00Z");
+        assert_eq!((&x).into_string(), "2024-03-01T12:30:00Z");
+    }
+
+    #[test]
+    fn fixed_offset() {
+        let offset = FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap();
+        let x = offset.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+        assert_eq!(x.into_string(), "2024-03-01T12:30:00+05:30");
+        let x = x + chrono::Duration::milliseconds(250);
+        assert_eq!(x.into_string(), "2024-03-01T12:30:00.250+05:30");
+        // zero as a fixed offset is still `Z`
+        let x = FixedOffset::east_opt(0).unwrap().with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(x.into_string(), "2024-03-01T00:00:00Z");
+    }
+
+    #[test]
+    fn fraction_digits() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let at = |nanos| Utc.from_utc_datetime(&date.and_hms_nano_opt(12, 30, 0, nanos).unwrap());
+        assert_eq!(at(123_456_789).into_string(), "2024-03-01T12:30:00.123456789Z");
+        assert_eq!(at(123_456_000).into_string(), "2024-03-01T12:30:00.123456Z");
+        assert_eq!(at(1).into_string(), "2024-03-01T12:30:00.000000001Z");
+    }
+
+    #[test]
+    fn naive() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(date.and_hms_opt(12, 30, 0).unwrap().into_string(), "2024-03-01T12:30:00");
+        let x = date.and_hms_milli_opt(12, 30, 0, 250).unwrap();
+        assert_eq!(x.into_string(), "2024-03-01T12:30:00.250");
+        assert_eq!((&date.and_hms_nano_opt(0, 0, 0, 7).unwrap()).into_string(), "2024-03-01T00:00:00.000000007");
+    }
+}
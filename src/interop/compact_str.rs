@@ -0,0 +1,68 @@
+use compact_str::CompactString;
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for CompactString {
+    /// Special case, a heap allocated `CompactString` hands over its buffer
+    fn into_string(self) -> String {
+        CompactString::into_string(self)
+    }
+}
+impl Sealed for CompactString {}
+impl IntoStringBase for CompactString {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// Converts _something_ into a
+/// [`CompactString`](https://docs.rs/compact_str/latest/compact_str/struct.CompactString.html)
+///
+/// Implemented for everything which implements `IntoString`, the lossy
+/// conversion is the exact same. When the converted text is short enough
+/// it is stored inline rather than on the heap.
+pub trait IntoCompactString {
+    fn into_compact_string(self) -> CompactString;
+}
+
+impl<T: IntoString> IntoCompactString for T {
+    fn into_compact_string(self) -> CompactString {
+        CompactString::from(self.into_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{OsStr,OsString};
+
+    use super::*;
+
+    #[test]
+    fn compact_string_input() {
+        let long = CompactString::from("a string which is too long to be inline");
+        assert!(long.is_heap_allocated());
+        let ptr = long.as_ptr();
+        let text = IntoString::into_string(long);
+        assert_eq!(text.as_ptr(), ptr);
+        assert_eq!((&CompactString::from("short")).into_string(), "short");
+    }
+
+    #[test]
+    fn short_os_str_is_inline() {
+        let out = OsStr::new("short name").into_compact_string();
+        assert_eq!(out, "short name");
+        assert!(!out.is_heap_allocated());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_os_string() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let name = OsString::from_vec(b"bad\xFFname".to_vec());
+        let out = (&name).into_compact_string();
+        assert_eq!(out, "bad\u{FFFD}name");
+        assert_eq!(out, (&name).into_string());
+        assert_eq!(name.into_compact_string(), "bad\u{FFFD}name");
+    }
+}
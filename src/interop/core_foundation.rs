@@ -0,0 +1,88 @@
+//! `CFString` is stored either as 8 bit text or as utf16. When it is
+//! already utf8 the bytes are borrowed directly, anything else is copied
+//! out as utf16, where each unpaired surrogate becomes `U+FFFD`.
+
+use std::ffi::CStr;
+
+use core_foundation::{
+    base::{CFRange,TCFType},
+    string::{
+        CFString,
+        CFStringGetCStringPtr,
+        CFStringGetCharacters,
+        CFStringGetCharactersPtr,
+        CFStringGetLength,
+        kCFStringEncodingUTF8,
+    },
+};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_to_cow,local_wide_to_str};
+
+impl IntoString for CFString {
+    fn into_string(self) -> String {
+        cf_string_to_str(&self)
+    }
+}
+impl Sealed for CFString {}
+impl IntoStringBase for CFString {
+    fn ref_into_string(&self) -> String {
+        cf_string_to_str(self)
+    }
+}
+
+fn cf_string_to_str(s: &CFString) -> String {
+    let r = s.as_concrete_TypeRef();
+    // SAFETY: `r` is a live CFString for the whole function, the
+    // pointers CF hands out are only read before `s` is released
+    unsafe {
+        let utf8 = CFStringGetCStringPtr(r, kCFStringEncodingUTF8);
+        if !utf8.is_null() {
+            return local_to_cow("CFString", CStr::from_ptr(utf8).to_bytes()).into_owned();
+        }
+        let len = CFStringGetLength(r);
+        if len <= 0 {
+            return String::new();
+        }
+        let wide = CFStringGetCharactersPtr(r);
+        if !wide.is_null() {
+            return local_wide_to_str(std::slice::from_raw_parts(wide, len as usize));
+        }
+        let mut buf = vec![0u16; len as usize];
+        CFStringGetCharacters(r, CFRange { location: 0, length: len }, buf.as_mut_ptr());
+        local_wide_to_str(&buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core_foundation::{
+        base::kCFAllocatorDefault,
+        string::CFStringCreateWithCharacters,
+    };
+
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        let s = CFString::new("plain ascii");
+        assert_eq!((&s).into_string(), "plain ascii");
+        assert_eq!(s.into_string(), "plain ascii");
+        assert_eq!(CFString::new("").into_string(), "");
+    }
+
+    #[test]
+    fn non_bmp() {
+        assert_eq!(CFString::new("smile 😀 日本").into_string(), "smile 😀 日本");
+    }
+
+    #[test]
+    fn lone_surrogate() {
+        let wide = [0x61u16, 0xD800, 0x62];
+        // SAFETY: `wide` outlives the call, the new string is owned here
+        let s = unsafe {
+            let r = CFStringCreateWithCharacters(kCFAllocatorDefault, wide.as_ptr(), wide.len() as _);
+            CFString::wrap_under_create_rule(r)
+        };
+        assert_eq!(s.into_string(), "a\u{FFFD}b");
+    }
+}
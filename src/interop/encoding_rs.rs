@@ -0,0 +1,123 @@
+use std::ffi::{CStr,CString};
+#[cfg(unix)]
+use std::{
+    ffi::{OsStr,OsString},
+    os::unix::ffi::OsStrExt,
+};
+
+use encoding_rs::Encoding;
+
+/// Decodes `bytes` as `enc`, malformed sequences become `U+FFFD`.
+///
+/// This is [`Encoding::decode`](https://docs.rs/encoding_rs/latest/encoding_rs/struct.Encoding.html#method.decode),
+/// so a BOM at the start of `bytes` wins over `enc` (a UTF-16 BOM will
+/// be decoded as UTF-16 even when `enc` is Shift_JIS) and is removed.
+pub fn bytes_into_string_with(bytes: &[u8], enc: &'static Encoding) -> String {
+    let (text, _, _) = enc.decode(bytes);
+    text.into_owned()
+}
+
+/// Conversion of byte backed types from a legacy encoding,
+/// see [`bytes_into_string_with`].
+///
+/// `OsStr`/`OsString` are only supported on Unix, where they are bytes.
+pub trait IntoStringWithEncoding {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String;
+}
+
+impl IntoStringWithEncoding for &[u8] {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self, enc)
+    }
+}
+impl IntoStringWithEncoding for Vec<u8> {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(&self, enc)
+    }
+}
+impl IntoStringWithEncoding for &Vec<u8> {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self, enc)
+    }
+}
+impl IntoStringWithEncoding for &CStr {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.to_bytes(), enc)
+    }
+}
+impl IntoStringWithEncoding for CString {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.as_bytes(), enc)
+    }
+}
+impl IntoStringWithEncoding for &CString {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.as_bytes(), enc)
+    }
+}
+#[cfg(unix)]
+impl IntoStringWithEncoding for &OsStr {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.as_bytes(), enc)
+    }
+}
+#[cfg(unix)]
+impl IntoStringWithEncoding for OsString {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.as_bytes(), enc)
+    }
+}
+#[cfg(unix)]
+impl IntoStringWithEncoding for &OsString {
+    fn into_string_with_encoding(self, enc: &'static Encoding) -> String {
+        bytes_into_string_with(self.as_bytes(), enc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{SHIFT_JIS,UTF_8,WINDOWS_1252};
+
+    use super::*;
+
+    #[test]
+    fn shift_jis() {
+        let bytes: &[u8] = b"\x93\xFA\x96\x7B\x8C\xEA.txt";
+        assert_eq!(bytes_into_string_with(bytes, SHIFT_JIS), "日本語.txt");
+        assert_eq!(bytes.to_vec().into_string_with_encoding(SHIFT_JIS), "日本語.txt");
+    }
+
+    #[test]
+    fn windows_1252() {
+        let bytes: &[u8] = b"caf\xE9 \x80 \x93q\x94";
+        assert_eq!(bytes.into_string_with_encoding(WINDOWS_1252), "café € “q”");
+        let c = CString::new(bytes).unwrap();
+        assert_eq!(c.as_c_str().into_string_with_encoding(WINDOWS_1252), "café € “q”");
+        assert_eq!(c.into_string_with_encoding(WINDOWS_1252), "café € “q”");
+    }
+
+    #[test]
+    fn bom_wins_over_the_requested_encoding() {
+        let bytes: &[u8] = b"\xFF\xFEh\x00i\x00";
+        assert_eq!(bytes_into_string_with(bytes, SHIFT_JIS), "hi");
+        assert_eq!(bytes_into_string_with(b"\xEF\xBB\xBFcaf\xC3\xA9", WINDOWS_1252), "café");
+    }
+
+    #[test]
+    fn malformed_is_replaced() {
+        // a lead byte with nothing after it
+        assert_eq!(bytes_into_string_with(b"a\x93", SHIFT_JIS), "a\u{FFFD}");
+        assert_eq!(bytes_into_string_with(b"a\xFFb", UTF_8), "a\u{FFFD}b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_str_on_unix() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let name = OsString::from_vec(b"\x93\xFA\x96\x7B".to_vec());
+        assert_eq!(name.as_os_str().into_string_with_encoding(SHIFT_JIS), "日本");
+        assert_eq!((&name).into_string_with_encoding(SHIFT_JIS), "日本");
+        assert_eq!(name.into_string_with_encoding(SHIFT_JIS), "日本");
+    }
+}
@@ -0,0 +1,74 @@
+//! Collecting a stream of byte chunks (an http body for example) into a
+//! `String`, without joining the chunks first.
+
+use std::pin::pin;
+
+use futures::{Stream,StreamExt};
+
+use crate::LossyDecoder;
+
+/// Collects every chunk of `stream` into a `String`.
+///
+/// The chunks are converted one at a time, a sequence split between two
+/// chunks is still decoded correctly (at most 3 bytes are held back). The
+/// result is the same as `String::from_utf8_lossy` over all the chunks
+/// joined together.
+///
+/// The first `Err` from the stream is returned and the rest of the
+/// stream is not polled.
+pub async fn collect_string_lossy<S, B, E>(stream: S) -> Result<String, E>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+{
+    let mut stream = pin!(stream);
+    let mut decoder = LossyDecoder::new();
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        decoder.push_to(chunk?.as_ref(), &mut text);
+    }
+    decoder.finish_to(&mut text);
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on,stream};
+
+    use super::*;
+
+    fn collect(chunks: &[&[u8]]) -> String {
+        let stream = stream::iter(chunks.iter().map(Ok::<_, ()>));
+        block_on(collect_string_lossy(stream)).unwrap()
+    }
+
+    #[test]
+    fn emoji_split_across_chunks() {
+        let emoji = "😀".as_bytes();
+        assert_eq!(collect(&[b"a", &emoji[..1], &emoji[1..3], &emoji[3..], b"b"]), "a😀b");
+        assert_eq!(collect(&[&emoji[..2], &emoji[2..]]), "😀");
+    }
+
+    #[test]
+    fn invalid_byte_at_a_boundary() {
+        assert_eq!(collect(&[b"ab\xFF", b"\xFEcd"]), "ab\u{FFFD}\u{FFFD}cd");
+        assert_eq!(collect(&[b"a\xE2\x82", b"b"]), "a\u{FFFD}b");
+        assert_eq!(collect(&[b"a", b"\xF0\x9F"]), "a\u{FFFD}");
+        assert_eq!(collect(&[]), "");
+    }
+
+    #[test]
+    fn same_as_joined() {
+        let all: Vec<u8> = crate::TEST_BYTES.concat();
+        for size in [1, 2, 3, 5, 64] {
+            let chunks: Vec<&[u8]> = all.chunks(size).collect();
+            assert_eq!(collect(&chunks), String::from_utf8_lossy(&all));
+        }
+    }
+
+    #[test]
+    fn first_error_stops_the_stream() {
+        let chunks = [Ok(&b"a"[..]), Err("broken"), Ok(b"never polled")];
+        assert_eq!(block_on(collect_string_lossy(stream::iter(chunks))), Err("broken"));
+    }
+}
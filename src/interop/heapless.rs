@@ -0,0 +1,88 @@
+//! Output into `heapless::String`, for targets which can't allocate.
+
+use heapless::{CapacityError,String};
+
+use crate::fixed::{FixedBuf,LossyPieces,Writer};
+
+/// Converts into a `heapless::String<N>` with the same lossy rules as
+/// `into_string`, without allocating.
+///
+/// Implemented for `&str`, `&CStr`, `&[u8]` (replaced like
+/// `String::from_utf8_lossy`), `char` and the integer types. An integer
+/// is never cut part way through its digits, when it does not fit the
+/// truncating conversion leaves it out entirely.
+pub trait IntoHeaplessString<const N: usize> {
+    /// Fails when the result is longer than `N` bytes
+    fn into_heapless(self) -> Result<String<N>, CapacityError>;
+
+    /// Keeps as much as fits in `N` bytes, cut at a char boundary
+    fn into_heapless_truncating(self) -> String<N>;
+}
+
+impl<T: LossyPieces, const N: usize> IntoHeaplessString<N> for T {
+    fn into_heapless(self) -> Result<String<N>, CapacityError> {
+        let mut out = String::new();
+        match self.write_pieces(&mut Writer::new(&mut out, false)) {
+            Ok(()) => Ok(out),
+            Err(_) => Err(CapacityError::default()),
+        }
+    }
+    fn into_heapless_truncating(self) -> String<N> {
+        let mut out = String::new();
+        let _ = self.write_pieces(&mut Writer::new(&mut out, true));
+        out
+    }
+}
+
+impl<const N: usize> FixedBuf for String<N> {
+    fn try_push_str(&mut self, s: &str) -> bool {
+        self.push_str(s).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_fit() {
+        let out: String<5> = "héll".into_heapless().unwrap();
+        assert_eq!(out, "héll");
+        assert!(IntoHeaplessString::<4>::into_heapless("héll").is_err());
+        let out: String<3> = 'é'.into_heapless().unwrap();
+        assert_eq!(out, "é");
+    }
+
+    #[test]
+    fn truncated_on_a_char_boundary() {
+        // the `é` is bytes 1 and 2, there is only room for byte 1
+        let out: String<2> = "hé".into_heapless_truncating();
+        assert_eq!(out, "h");
+        let out: String<6> = "ab😀".into_heapless_truncating();
+        assert_eq!(out, "ab😀");
+        let out: String<5> = "ab😀".into_heapless_truncating();
+        assert_eq!(out, "ab");
+    }
+
+    #[test]
+    fn lossy_sources() {
+        let out: String<16> = c"a\xFFb".into_heapless().unwrap();
+        assert_eq!(out, "\u{FFFD}\u{FFFD}\u{FFFD}");
+        let out: String<16> = (&b"a\xFFb\xE2\x82"[..]).into_heapless().unwrap();
+        assert_eq!(out, "a\u{FFFD}b\u{FFFD}");
+        // the replacement does not fit after `a`
+        let out: String<3> = (&b"a\xFF"[..]).into_heapless_truncating();
+        assert_eq!(out, "a");
+    }
+
+    #[test]
+    fn integers_are_never_split() {
+        let out: String<20> = i64::MIN.into_heapless().unwrap();
+        assert_eq!(out, "-9223372036854775808");
+        let out: String<40> = i128::MIN.into_heapless().unwrap();
+        assert_eq!(out, "-170141183460469231731687303715884105728");
+        let out: String<2> = 123u8.into_heapless_truncating();
+        assert_eq!(out, "");
+        assert!(IntoHeaplessString::<2>::into_heapless(-12i8).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! `HeaderValue::to_str` fails on any byte which is not visible ascii,
+//! here the value is instead converted like `String::from_utf8_lossy`. A
+//! value holding utf8 keeps it, any other byte past `0x7F` (obs-text, such
+//! as latin-1) becomes `U+FFFD`.
+//!
+//! HTTP itself says the bytes past `0x7F` are ISO-8859-1, for a peer which
+//! actually sends Latin-1 use [`IntoStringLatin1`] or
+//! [`header_map_into_strings_latin1`], which decode every byte as Latin-1
+//! (so utf8 in the value comes out as one character per byte instead).
+
+use http::{HeaderMap,HeaderValue};
+
+use crate::{IntoString,IntoStringBase,IntoStringLatin1,bytes_into_string_latin1,sealed::Sealed,local_to_cow};
+
+impl IntoString for HeaderValue {
+    fn into_string(self) -> String {
+        header_value_to_str(&self)
+    }
+}
+impl Sealed for HeaderValue {}
+impl IntoStringBase for HeaderValue {
+    fn ref_into_string(&self) -> String {
+        header_value_to_str(self)
+    }
+}
+
+impl IntoStringLatin1 for HeaderValue {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self.as_bytes())
+    }
+}
+impl IntoStringLatin1 for &HeaderValue {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self.as_bytes())
+    }
+}
+
+/// Converts every header in `map` into a `(name, value)` pair.
+///
+/// A name which appears more than once gives one pair per value, in the
+/// order of `HeaderMap::iter`.
+pub fn header_map_into_strings(map: &HeaderMap) -> Vec<(String, String)> {
+    map.iter()
+        .map(|(name, value)| (name.as_str().to_string(), header_value_to_str(value)))
+        .collect()
+}
+
+/// Same as [`header_map_into_strings`], with the values decoded as Latin-1
+pub fn header_map_into_strings_latin1(map: &HeaderMap) -> Vec<(String, String)> {
+    map.iter()
+        .map(|(name, value)| (name.as_str().to_string(), bytes_into_string_latin1(value.as_bytes())))
+        .collect()
+}
+
+fn header_value_to_str(value: &HeaderValue) -> String {
+    match value.to_str() {
+        Ok(s) => s.to_string(),
+        Err(_) => local_to_cow("HeaderValue", value.as_bytes()).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::{CONTENT_DISPOSITION,HOST,SET_COOKIE};
+
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        let value = HeaderValue::from_static("example.com");
+        assert_eq!((&value).into_string(), "example.com");
+        assert_eq!((&value).into_string_latin1(), "example.com");
+        assert_eq!(value.into_string(), "example.com");
+    }
+
+    #[test]
+    fn latin1_byte() {
+        let value = HeaderValue::from_bytes(b"attachment; filename=\"caf\xE9.txt\"").unwrap();
+        assert_eq!((&value).into_string(), "attachment; filename=\"caf\u{FFFD}.txt\"");
+        assert_eq!(value.into_string_latin1(), "attachment; filename=\"café.txt\"");
+        // utf8 is kept by default, and is one character per byte as latin-1
+        let value = HeaderValue::from_bytes("café".as_bytes()).unwrap();
+        assert_eq!((&value).into_string(), "café");
+        assert_eq!(value.into_string_latin1(), "cafÃ©");
+    }
+
+    #[test]
+    fn binary() {
+        let value = HeaderValue::from_bytes(b"\x80\xFF\t\xFE").unwrap();
+        assert_eq!(value.into_string(), "\u{FFFD}\u{FFFD}\t\u{FFFD}");
+    }
+
+    #[test]
+    fn header_maps() {
+        let mut map = HeaderMap::new();
+        map.insert(HOST, HeaderValue::from_static("example.com"));
+        map.append(SET_COOKIE, HeaderValue::from_static("a=1"));
+        map.append(SET_COOKIE, HeaderValue::from_static("b=2"));
+        map.insert(CONTENT_DISPOSITION, HeaderValue::from_bytes(b"n\xE9").unwrap());
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+        assert_eq!(header_map_into_strings(&map), [
+            pair("host", "example.com"),
+            pair("set-cookie", "a=1"),
+            pair("set-cookie", "b=2"),
+            pair("content-disposition", "n\u{FFFD}"),
+        ]);
+        assert_eq!(header_map_into_strings_latin1(&map)[3], pair("content-disposition", "né"));
+    }
+}
@@ -0,0 +1,74 @@
+//! `addr/prefix` exactly as `Display` writes it, so IPv6 is compressed
+//! the same way std compresses `Ipv6Addr`.
+
+use ipnet::{IpNet,Ipv4Net,Ipv6Net};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for IpNet {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for IpNet {}
+impl IntoStringBase for IpNet {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoString for Ipv4Net {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for Ipv4Net {}
+impl IntoStringBase for Ipv4Net {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoString for Ipv6Net {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for Ipv6Net {}
+impl IntoStringBase for Ipv6Net {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_display() {
+        for text in ["192.0.2.1/32", "0.0.0.0/0", "10.0.0.0/8", "2001:db8::1/128", "::/0", "2001:db8:0:0:1::/80"] {
+            let net: IpNet = text.parse().unwrap();
+            assert_eq!((&net).into_string(), net.to_string());
+            assert_eq!(net.into_string(), net.to_string());
+        }
+    }
+
+    #[test]
+    fn host_and_default_routes() {
+        let v4: Ipv4Net = "192.0.2.1/32".parse().unwrap();
+        assert_eq!(v4.into_string(), "192.0.2.1/32");
+        assert_eq!(Ipv4Net::default().into_string(), "0.0.0.0/0");
+        let v6: Ipv6Net = "2001:db8::1/128".parse().unwrap();
+        assert_eq!((&v6).into_string(), "2001:db8::1/128");
+        assert_eq!(Ipv6Net::default().into_string(), "::/0");
+    }
+
+    #[test]
+    fn compressed_ipv6() {
+        let net: Ipv6Net = "2001:0db8:0000:0000:0001:0000:0000:0000/80".parse().unwrap();
+        assert_eq!(net.into_string(), "2001:db8:0:0:1::/80");
+        let net: IpNet = "fe80:0:0:0:0:0:0:0/10".parse().unwrap();
+        assert_eq!(net.into_string(), "fe80::/10");
+    }
+}
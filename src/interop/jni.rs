@@ -0,0 +1,188 @@
+//! Java hands strings to native code as _modified_ utf8: NUL is written
+//! as `0xC0 0x80` and everything past `U+FFFF` as a pair of 3 byte
+//! surrogates (CESU-8). Plain utf8 validation rejects both, so these are
+//! decoded with [`modified_utf8_into_string`] instead.
+
+use jni::{
+    objects::JString,
+    refs::Reference,
+    strings::{JNIStr,JNIString,MUTF8Chars},
+};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_validate};
+
+/// Decodes modified utf8 (as returned by `GetStringUTFChars`) into a `String`.
+///
+/// `0xC0 0x80` becomes NUL and surrogate pairs are joined back together.
+/// Unpaired surrogates and other invalid sequences become `U+FFFD`. Input
+/// which is already valid utf8 (so plain 4 byte sequences too) is copied
+/// as is.
+pub fn modified_utf8_into_string(bytes: &[u8]) -> String {
+    if let Some(s) = local_validate(bytes) {
+        return s.to_owned();
+    }
+    let mut out = String::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let (c, used) = decode_one(&bytes[i..]);
+        out.push(c);
+        i += used;
+    }
+    out
+}
+
+impl Sealed for JNIStr {}
+impl IntoStringBase for JNIStr {
+    fn ref_into_string(&self) -> String {
+        modified_utf8_into_string(self.to_bytes())
+    }
+}
+
+impl IntoString for JNIString {
+    fn into_string(self) -> String {
+        modified_utf8_into_string(self.to_bytes())
+    }
+}
+impl Sealed for JNIString {}
+impl IntoStringBase for JNIString {
+    fn ref_into_string(&self) -> String {
+        modified_utf8_into_string(self.to_bytes())
+    }
+}
+
+impl<'local, S> IntoString for MUTF8Chars<'local, S>
+where
+    S: AsRef<JString<'local>> + Reference,
+{
+    fn into_string(self) -> String {
+        modified_utf8_into_string(self.to_bytes())
+    }
+}
+
+// decodes the character at the start of `b`, returns it and the number
+// of bytes used. a bad sequence uses the longest start of a valid one
+// (same as `String::from_utf8_lossy`, except that `ED A0..=BF` starts a
+// surrogate here rather than being bad)
+fn decode_one(b: &[u8]) -> (char, usize) {
+    const BAD: char = char::REPLACEMENT_CHARACTER;
+    match b[0] {
+        lead @ 0x00..=0x7F => (lead as char, 1),
+        0xC0 if b.get(1) == Some(&0x80) => ('\0', 2),
+        lead @ 0xC2..=0xDF => match b.get(1) {
+            Some(&c) if is_cont(c) => {
+                let u = (u32::from(lead & 0x1F) << 6) | u32::from(c & 0x3F);
+                (char::from_u32(u).unwrap_or(BAD), 2)
+            }
+            _ => (BAD, 1),
+        },
+        0xE0..=0xEF => match three(b) {
+            Some(hi @ 0xD800..=0xDBFF) => match three(&b[3..]) {
+                Some(lo @ 0xDC00..=0xDFFF) => {
+                    let u = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                    (char::from_u32(u).unwrap_or(BAD), 6)
+                }
+                _ => (BAD, 3),
+            },
+            // a lone low surrogate fails `from_u32`
+            Some(u) => (char::from_u32(u).unwrap_or(BAD), 3),
+            None => (BAD, bad_len(b)),
+        },
+        0xF0..=0xF4 => match b.get(..4).and_then(|x| std::str::from_utf8(x).ok()) {
+            Some(s) => (s.chars().next().unwrap_or(BAD), 4),
+            None => (BAD, bad_len(b)),
+        },
+        _ => (BAD, 1),
+    }
+}
+
+// the 16 bit unit of a 3 byte sequence, surrogates included
+fn three(b: &[u8]) -> Option<u32> {
+    match *b {
+        [lead @ 0xE0..=0xEF, c1, c2, ..] if is_cont(c1) && is_cont(c2) && (lead != 0xE0 || c1 >= 0xA0) => {
+            Some((u32::from(lead & 0x0F) << 12) | (u32::from(c1 & 0x3F) << 6) | u32::from(c2 & 0x3F))
+        }
+        _ => None,
+    }
+}
+
+// the length of the bad sequence at the start of `b`, whose lead byte
+// starts a 3 or 4 byte sequence: the lead byte and the continuation bytes
+// after it, as long as the second byte is in the range its lead byte
+// allows (no overlong forms, nothing past `U+10FFFF`)
+fn bad_len(b: &[u8]) -> usize {
+    let (second, max) = match b[0] {
+        0xE0 => (0xA0..=0xBF, 2),
+        0xE1..=0xEF => (0x80..=0xBF, 2),
+        0xF0 => (0x90..=0xBF, 3),
+        0xF1..=0xF3 => (0x80..=0xBF, 3),
+        0xF4 => (0x80..=0x8F, 3),
+        _ => return 1,
+    };
+    match b.get(1) {
+        Some(c) if second.contains(c) => 2 + b[2..].iter().take(max - 1).take_while(|&&c| is_cont(c)).count(),
+        _ => 1,
+    }
+}
+
+fn is_cont(c: u8) -> bool {
+    c & 0xC0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn cesu8_pair_and_nul() {
+        // `😀` is the surrogates D83D DE00, 3 bytes each
+        assert_eq!(modified_utf8_into_string(b"a\xED\xA0\xBD\xED\xB8\x80b"), "a😀b");
+        assert_eq!(modified_utf8_into_string(b"a\xC0\x80b"), "a\0b");
+        assert_eq!(modified_utf8_into_string("plain 😀".as_bytes()), "plain 😀");
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        assert_eq!(modified_utf8_into_string(b"\xED\xA0\xBDx"), "\u{FFFD}x");
+        assert_eq!(modified_utf8_into_string(b"\xED\xB8\x80x"), "\u{FFFD}x");
+        // high then high, the second can still start a pair
+        assert_eq!(modified_utf8_into_string(b"\xED\xA0\xBD\xED\xA0\xBD\xED\xB8\x80"), "\u{FFFD}😀");
+    }
+
+    #[test]
+    fn overlong_and_out_of_range() {
+        assert_eq!(modified_utf8_into_string(b"\xE0\x80\x80"), "\u{FFFD}\u{FFFD}\u{FFFD}");
+        assert_eq!(modified_utf8_into_string(b"\xF0\x80\x80\x80"), "\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}");
+        assert_eq!(modified_utf8_into_string(b"\xF4\x90\x80\x80"), "\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}");
+        assert_eq!(modified_utf8_into_string(b"\xE2\x82x"), "\u{FFFD}x");
+        assert_eq!(modified_utf8_into_string(b"\xF0\x9F\x98"), "\u{FFFD}");
+    }
+
+    // everything but NUL and the surrogates is decoded the same as utf8
+    #[test]
+    fn otherwise_same_as_from_utf8_lossy() {
+        for bytes in TEST_BYTES.iter().filter(|x| !x.starts_with(b"\xC0\x80") && !x.starts_with(b"\xED")) {
+            assert_eq!(modified_utf8_into_string(bytes), String::from_utf8_lossy(bytes));
+        }
+        // every lead byte with every second byte, cut short and followed by
+        // a continuation byte and a plain one
+        for lead in (0x80..=0xFFu8).filter(|x| *x != 0xC0 && *x != 0xED) {
+            for second in (0..=0xFFu8).filter(|x| *x != 0xC0 && *x != 0xED) {
+                for bytes in [&[lead, second][..], &[lead, second, 0x80, b'x'], &[lead, second, 0x80, 0x80, b'x']] {
+                    assert_eq!(modified_utf8_into_string(bytes), String::from_utf8_lossy(bytes), "{bytes:02X?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn jni_strings() {
+        let s = JNIString::new("nul\0 and 😀");
+        assert_eq!((&s).into_string(), "nul\0 and 😀");
+        assert_eq!(s.borrowed().into_string(), "nul\0 and 😀");
+        assert_eq!(s.into_string(), "nul\0 and 😀");
+        // SAFETY: no interior NUL, the contents are what is being tested
+        let s = unsafe { JNIStr::from_cstr_unchecked(c"\xED\xA0\xBD\xED\xB8\x80") };
+        assert_eq!(s.into_string(), "😀");
+    }
+}
@@ -0,0 +1,165 @@
+//! Implementations for types from other crates, each behind
+//! a feature of the same name.
+
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "anyhow")]
+pub use self::anyhow::anyhow_chain_string;
+
+#[cfg(feature = "arcstr")]
+mod arcstr;
+#[cfg(feature = "arcstr")]
+pub use self::arcstr::IntoArcStr;
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+#[cfg(feature = "arrayvec")]
+pub use self::arrayvec::{IntoArrayString,Overflow};
+
+#[cfg(feature = "ascii")]
+mod ascii;
+#[cfg(feature = "ascii")]
+pub use self::ascii::IntoAsciiStringLossy;
+
+#[cfg(feature = "beef")]
+mod beef;
+
+#[cfg(feature = "bstr")]
+mod bstr;
+
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "bytes")]
+pub use self::bytes::buf_into_string;
+
+#[cfg(feature = "camino")]
+mod camino;
+
+#[cfg(all(target_vendor = "apple", feature = "core-foundation"))]
+mod core_foundation;
+
+#[cfg(feature = "detect")]
+mod chardetng;
+#[cfg(feature = "detect")]
+pub use self::chardetng::bytes_into_string_detected;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "compact-str")]
+mod compact_str;
+#[cfg(feature = "compact-str")]
+pub use self::compact_str::IntoCompactString;
+
+#[cfg(feature = "encoding")]
+mod encoding_rs;
+#[cfg(feature = "encoding")]
+pub use self::encoding_rs::{IntoStringWithEncoding,bytes_into_string_with};
+
+#[cfg(feature = "futures")]
+mod futures;
+#[cfg(feature = "futures")]
+pub use self::futures::collect_string_lossy;
+
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(feature = "heapless")]
+pub use self::heapless::IntoHeaplessString;
+
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "http")]
+pub use self::http::{header_map_into_strings,header_map_into_strings_latin1};
+
+#[cfg(feature = "ipnet")]
+mod ipnet;
+
+#[cfg(feature = "jni")]
+mod jni;
+#[cfg(feature = "jni")]
+pub use self::jni::modified_utf8_into_string;
+
+#[cfg(all(target_vendor = "apple", feature = "objc2"))]
+mod objc2;
+
+#[cfg(feature = "num-bigint")]
+mod num_bigint;
+
+#[cfg(feature = "os-str-bytes")]
+mod os_str_bytes;
+
+#[cfg(feature = "pyo3")]
+mod pyo3;
+#[cfg(feature = "pyo3")]
+pub use self::pyo3::py_any_into_string;
+
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "rayon")]
+pub use self::rayon::{PAR_THRESHOLD,par_into_strings,par_join_to_string};
+
+#[cfg(feature = "rust-decimal")]
+mod rust_decimal;
+#[cfg(feature = "rust-decimal")]
+pub use self::rust_decimal::decimal_into_string_normalized;
+
+#[cfg(feature = "semver")]
+mod semver;
+
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "serde")]
+pub use self::serde::LossyStr;
+#[cfg(feature = "serde")]
+pub mod serde_lossy;
+
+#[cfg(feature = "smallvec")]
+mod smallvec;
+
+#[cfg(feature = "smartstring")]
+mod smartstring;
+#[cfg(feature = "smartstring")]
+pub use self::smartstring::IntoSmartString;
+
+#[cfg(feature = "smol-str")]
+mod smol_str;
+#[cfg(feature = "smol-str")]
+pub use self::smol_str::IntoSmolStr;
+
+#[cfg(feature = "time")]
+mod time;
+
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use self::tokio::{AsyncReadLossyExt,read_to_string_lossy_async};
+
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(feature = "tracing")]
+pub use self::tracing::{DEFAULT_LOSSY_WARNING_LIMIT,set_lossy_warning_limit};
+#[cfg(feature = "tracing")]
+pub(crate) use self::tracing::warn_lossy;
+
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "url")]
+pub use self::url::url_into_decoded_string;
+
+#[cfg(feature = "uuid")]
+mod uuid;
+#[cfg(feature = "uuid")]
+pub use self::uuid::{uuid_into_simple_string,uuid_into_urn_string};
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use self::wasm::{JS_VALUE_FALLBACK,js_value_into_string};
+
+#[cfg(feature = "widestring")]
+mod widestring;
+
+#[cfg(all(windows, feature = "windows"))]
+mod windows;
+#[cfg(all(windows, feature = "windows"))]
+pub use self::windows::{pcwstr_into_string,pwstr_into_string};
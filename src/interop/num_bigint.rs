@@ -0,0 +1,109 @@
+//! `BigInt` and `BigUint` in decimal, see [`IntoStringRadix`] for others.
+
+use num_bigint::{BigInt,BigUint};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,IntoStringRadix,radix::check_radix};
+
+impl IntoString for BigInt {
+    fn into_string(self) -> String {
+        self.to_str_radix(10)
+    }
+}
+impl Sealed for BigInt {}
+impl IntoStringBase for BigInt {
+    fn ref_into_string(&self) -> String {
+        self.to_str_radix(10)
+    }
+}
+
+impl IntoString for BigUint {
+    fn into_string(self) -> String {
+        self.to_str_radix(10)
+    }
+}
+impl Sealed for BigUint {}
+impl IntoStringBase for BigUint {
+    fn ref_into_string(&self) -> String {
+        self.to_str_radix(10)
+    }
+}
+
+impl IntoStringRadix for BigInt {
+    fn into_string_radix(self, radix: u32) -> String {
+        check_radix(radix);
+        self.to_str_radix(radix)
+    }
+}
+impl IntoStringRadix for &BigInt {
+    fn into_string_radix(self, radix: u32) -> String {
+        check_radix(radix);
+        self.to_str_radix(radix)
+    }
+}
+deep_radix_refs!(BigInt);
+
+impl IntoStringRadix for BigUint {
+    fn into_string_radix(self, radix: u32) -> String {
+        check_radix(radix);
+        self.to_str_radix(radix)
+    }
+}
+impl IntoStringRadix for &BigUint {
+    fn into_string_radix(self, radix: u32) -> String {
+        check_radix(radix);
+        self.to_str_radix(radix)
+    }
+}
+deep_radix_refs!(BigUint);
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::Sign;
+
+    use super::*;
+
+    // 4096 bit values from a xorshift, random enough for round trips
+    fn large_values() -> Vec<BigUint> {
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        (0..8).map(|_| {
+            let bytes: Vec<u8> = (0..512).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            }).collect();
+            BigUint::from_bytes_le(&bytes)
+        }).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for x in large_values() {
+            let negative = BigInt::from_biguint(Sign::Minus, x.clone());
+            assert_eq!((&x).into_string(), x.to_string());
+            assert_eq!((&negative).into_string(), negative.to_string());
+            for radix in [2, 10, 16, 36] {
+                let text = (&x).into_string_radix(radix);
+                assert_eq!(BigUint::parse_bytes(text.as_bytes(), radix), Some(x.clone()));
+                let text = (&negative).into_string_radix(radix);
+                assert!(text.starts_with('-'));
+                assert_eq!(BigInt::parse_bytes(text.as_bytes(), radix), Some(negative.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn zero_and_negative() {
+        assert_eq!(BigUint::ZERO.into_string(), "0");
+        assert_eq!(BigInt::ZERO.into_string_radix(2), "0");
+        assert_eq!(BigInt::from(-255).into_string(), "-255");
+        assert_eq!(BigInt::from(-255).into_string_radix(16), "-ff");
+        assert_eq!(BigUint::from(35u8).into_string_radix(36), "z");
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in 2..=36")]
+    fn radix_out_of_range() {
+        BigUint::from(1u8).into_string_radix(37);
+    }
+}
@@ -0,0 +1,91 @@
+//! `NSString` is utf16 underneath and may contain unpaired surrogates
+//! (each becomes `U+FFFD`). Strings which convert to utf8 cleanly are
+//! copied out of `UTF8String`, everything else is read as utf16.
+
+use std::{ffi::CStr,ptr::NonNull};
+
+use objc2::rc::Retained;
+use objc2_foundation::{NSRange,NSString,NSUTF8StringEncoding};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_to_cow,local_wide_to_str};
+
+impl Sealed for NSString {}
+impl IntoStringBase for NSString {
+    fn ref_into_string(&self) -> String {
+        ns_string_to_str(self)
+    }
+}
+
+impl IntoString for Retained<NSString> {
+    fn into_string(self) -> String {
+        ns_string_to_str(&self)
+    }
+}
+impl Sealed for Retained<NSString> {}
+impl IntoStringBase for Retained<NSString> {
+    fn ref_into_string(&self) -> String {
+        ns_string_to_str(self)
+    }
+}
+
+fn ns_string_to_str(s: &NSString) -> String {
+    let len = s.length();
+    if len == 0 {
+        return String::new();
+    }
+    // 0 when the string has no utf8 form (unpaired surrogates), and
+    // longer than the C string when there is an embedded NUL
+    let utf8_len = s.lengthOfBytesUsingEncoding(NSUTF8StringEncoding);
+    if utf8_len > 0 {
+        let ptr = s.UTF8String();
+        if !ptr.is_null() {
+            // SAFETY: not null and NUL terminated, it lives at least
+            // as long as `s` and is copied before returning
+            let bytes = unsafe { CStr::from_ptr(ptr) }.to_bytes();
+            if bytes.len() == utf8_len {
+                return local_to_cow("NSString", bytes).into_owned();
+            }
+        }
+    }
+    let mut buf = vec![0u16; len];
+    // SAFETY: `buf` has room for exactly `len` code units
+    unsafe {
+        s.getCharacters_range(NonNull::new_unchecked(buf.as_mut_ptr()), NSRange::new(0, len));
+    }
+    local_wide_to_str(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2::AllocAnyThread;
+
+    use super::*;
+
+    #[test]
+    fn ascii_and_cjk() {
+        let s = NSString::from_str("plain ascii");
+        assert_eq!((*s).into_string(), "plain ascii");
+        assert_eq!(s.into_string(), "plain ascii");
+        assert_eq!(NSString::from_str("日本語のテキスト 😀").into_string(), "日本語のテキスト 😀");
+        assert_eq!(NSString::from_str("").into_string(), "");
+    }
+
+    #[test]
+    fn embedded_nul() {
+        assert_eq!(NSString::from_str("a\0b").into_string(), "a\0b");
+    }
+
+    #[test]
+    fn lone_surrogate() {
+        let mut wide = [0x61u16, 0xD800, 0x62];
+        // SAFETY: `wide` is valid for `wide.len()` code units
+        let s = unsafe {
+            NSString::initWithCharacters_length(
+                NSString::alloc(),
+                NonNull::new_unchecked(wide.as_mut_ptr()),
+                wide.len(),
+            )
+        };
+        assert_eq!(s.into_string(), "a\u{FFFD}b");
+    }
+}
@@ -0,0 +1,58 @@
+//! These go through `OsStr`/`OsString` so the output is identical to
+//! the native impls, including unpaired surrogates on Windows.
+
+use std::ffi::OsString;
+
+use os_str_bytes::{RawOsStr,RawOsString};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for RawOsString {
+    /// Special Case
+    ///
+    /// Same as `OsString`, the buffer is transferred when it is valid utf8
+    fn into_string(self) -> String {
+        <OsString as IntoString>::into_string(self.into_os_string())
+    }
+}
+impl Sealed for RawOsString {}
+impl IntoStringBase for RawOsString {
+    fn ref_into_string(&self) -> String {
+        self.as_os_str().into_string()
+    }
+}
+
+impl Sealed for RawOsStr {}
+impl IntoStringBase for RawOsStr {
+    fn ref_into_string(&self) -> String {
+        self.as_os_str().into_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the same `OsString`s as text, and with bad sequences on Unix
+    fn samples() -> Vec<OsString> {
+        let mut samples = vec![OsString::new(), OsString::from("plain"), OsString::from("h\u{e9}llo")];
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+            samples.push(OsString::from_vec(b"a\xFFb\xC3".to_vec()));
+        }
+        samples
+    }
+
+    #[test]
+    fn same_as_os_string() {
+        for os in samples() {
+            let expected = <OsString as IntoString>::into_string(os.clone());
+            let raw = RawOsString::new(os);
+            let borrowed: &RawOsStr = &raw;
+            assert_eq!(borrowed.into_string(), expected);
+            assert_eq!((&raw).into_string(), expected);
+            assert_eq!(IntoString::into_string(raw), expected);
+        }
+    }
+}
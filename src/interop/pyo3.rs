@@ -0,0 +1,99 @@
+//! Python strings may hold lone surrogates (from `surrogateescape` for
+//! example), which `to_str` refuses. Here they become `U+FFFD`, one per
+//! surrogate. Python never pairs them up, so neither is a high surrogate
+//! followed by a low one, each is replaced.
+
+use pyo3::{
+    prelude::*,
+    types::{PyBytes,PyString,PyStringMethods},
+};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_wide32_to_str};
+
+impl IntoString for Bound<'_,PyString> {
+    fn into_string(self) -> String {
+        py_string_to_str(&self)
+    }
+}
+impl Sealed for Bound<'_,PyString> {}
+impl IntoStringBase for Bound<'_,PyString> {
+    fn ref_into_string(&self) -> String {
+        py_string_to_str(self)
+    }
+}
+
+/// Converts any Python object into a `String`.
+///
+/// Strings are converted like `PyString`, anything else goes through
+/// Python's `str()` first. Only an exception raised by `str()` is
+/// returned as `Err`.
+pub fn py_any_into_string(obj: &Bound<'_,PyAny>) -> PyResult<String> {
+    match obj.cast::<PyString>() {
+        Ok(s) => Ok(py_string_to_str(s)),
+        Err(_) => obj.str().map(|s| py_string_to_str(&s)),
+    }
+}
+
+fn py_string_to_str(s: &Bound<'_,PyString>) -> String {
+    if let Ok(text) = s.to_cow() {
+        return text.into_owned();
+    }
+    // `to_string_lossy` gives a `U+FFFD` per byte of each surrogate, so
+    // read the code points with the surrogates kept instead. utf32 rather
+    // than utf16, which would join a high and a low surrogate into one char
+    match s.call_method1("encode", ("utf-32-le", "surrogatepass")) {
+        Ok(bytes) => match bytes.cast::<PyBytes>() {
+            Ok(bytes) => {
+                let wide: Vec<u32> = bytes.as_bytes()
+                    .chunks_exact(4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                local_wide32_to_str(&wide)
+            }
+            Err(_) => s.to_string_lossy().into_owned(),
+        },
+        Err(_) => s.to_string_lossy().into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval<R>(code: &std::ffi::CStr, f: impl FnOnce(Bound<'_,PyAny>) -> R) -> R {
+        Python::initialize();
+        Python::attach(|py| f(py.eval(code, None, None).unwrap()))
+    }
+
+    #[test]
+    fn clean_string() {
+        let text = eval(c"'caf\\u00e9 \\U0001F600'", |obj| obj.cast_into::<PyString>().unwrap().into_string());
+        assert_eq!(text, "café 😀");
+    }
+
+    #[test]
+    fn surrogateescape() {
+        // the `\xFF` byte decodes to the lone surrogate `\udcff`
+        let code = c"b'bad\\xffname'.decode('utf-8', 'surrogateescape')";
+        let text = eval(code, |obj| obj.cast_into::<PyString>().unwrap().into_string());
+        assert_eq!(text, "bad\u{FFFD}name");
+        let text = eval(c"'\\ud800\\udc00x\\udfff'", |obj| py_any_into_string(&obj).unwrap());
+        assert_eq!(text, "\u{FFFD}\u{FFFD}x\u{FFFD}");
+        // a real non-BMP char next to a surrogate is kept
+        let text = eval(c"'\\U0001F600\\udcff'", |obj| py_any_into_string(&obj).unwrap());
+        assert_eq!(text, "😀\u{FFFD}");
+    }
+
+    #[test]
+    fn non_string_objects() {
+        assert_eq!(eval(c"42", |obj| py_any_into_string(&obj).unwrap()), "42");
+        assert_eq!(eval(c"[1, 'a']", |obj| py_any_into_string(&obj).unwrap()), "[1, 'a']");
+        assert_eq!(eval(c"None", |obj| py_any_into_string(&obj).unwrap()), "None");
+    }
+
+    #[test]
+    fn str_raising_is_an_error() {
+        let code = c"type('Bad', (), {'__str__': lambda self: 1 / 0})()";
+        assert!(eval(code, |obj| py_any_into_string(&obj).is_err()));
+    }
+}
@@ -0,0 +1,68 @@
+//! Converting a whole collection at once on rayon's thread pool.
+
+use rayon::prelude::*;
+
+use crate::IntoString;
+
+/// Inputs shorter than this are converted on the calling thread, splitting
+/// them up costs more than it saves.
+pub const PAR_THRESHOLD: usize = 4096;
+
+/// Converts every element of `v`, in parallel, keeping the order.
+///
+/// Each element is converted by value, so the owned special cases apply
+/// (an `OsString` which is valid utf8 hands over its buffer). Below
+/// [`PAR_THRESHOLD`] elements this is the same as a plain serial `map`.
+pub fn par_into_strings<T: IntoString + Send>(v: Vec<T>) -> Vec<String> {
+    if v.len() < PAR_THRESHOLD {
+        return v.into_iter().map(IntoString::into_string).collect();
+    }
+    v.into_par_iter().map(IntoString::into_string).collect()
+}
+
+/// Converts every element of `v` as [`par_into_strings`] does, and joins
+/// them with `sep` between each one.
+pub fn par_join_to_string<T: IntoString + Send>(v: Vec<T>, sep: &str) -> String {
+    par_into_strings(v).join(sep)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+    use crate::TEST_BYTES;
+
+    // enough copies of the corpus to go past the threshold
+    fn corpus(copies: usize) -> Vec<CString> {
+        let valid = TEST_BYTES.iter().filter(|x| !x.contains(&0));
+        valid.cycle().take(copies).map(|x| CString::new(*x).unwrap()).collect()
+    }
+
+    #[test]
+    fn same_as_serial() {
+        for len in [0, 10, PAR_THRESHOLD - 1, PAR_THRESHOLD, 3 * PAR_THRESHOLD + 7] {
+            let v = corpus(len);
+            let serial: Vec<String> = v.iter().map(|x| x.as_c_str().into_string()).collect();
+            assert_eq!(par_into_strings(v.clone()), serial);
+            assert_eq!(par_join_to_string(v, ", "), serial.join(", "));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_strings_keep_their_buffers() {
+        use std::{ffi::OsString,os::unix::ffi::OsStringExt};
+
+        let v: Vec<OsString> = TEST_BYTES.iter().cycle().take(2 * PAR_THRESHOLD).map(|x| OsString::from_vec(x.to_vec())).collect();
+        let serial: Vec<String> = v.iter().map(|x| x.into_string()).collect();
+        let ptrs: Vec<_> = v.iter().map(|x| x.as_encoded_bytes().as_ptr()).collect();
+        let out = par_into_strings(v);
+        assert_eq!(out, serial);
+        for ((text, ptr), bytes) in out.iter().zip(ptrs).zip(TEST_BYTES.iter().cycle()) {
+            if std::str::from_utf8(bytes).is_ok() && !bytes.is_empty() {
+                assert_eq!(text.as_ptr(), ptr);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! The canonical `Display` output, which never uses scientific notation
+//! and keeps the scale (`1.50` stays `1.50`).
+
+use rust_decimal::Decimal;
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for Decimal {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for Decimal {}
+impl IntoStringBase for Decimal {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Without trailing zeros, `1.500` becomes `1.5` and `10.0` becomes `10`
+pub fn decimal_into_string_normalized(value: &Decimal) -> String {
+    value.normalize().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative() {
+        assert_eq!(Decimal::new(-12345, 2).into_string(), "-123.45");
+        assert_eq!((&Decimal::new(-5, 3)).into_string(), "-0.005");
+        assert_eq!(decimal_into_string_normalized(&Decimal::new(-12340, 3)), "-12.34");
+    }
+
+    #[test]
+    fn trailing_zeros() {
+        let x = Decimal::new(1500, 3);
+        assert_eq!(x.into_string(), "1.500");
+        assert_eq!(decimal_into_string_normalized(&x), "1.5");
+        assert_eq!(decimal_into_string_normalized(&Decimal::new(100, 1)), "10");
+        assert_eq!(decimal_into_string_normalized(&Decimal::new(0, 5)), "0");
+        // no scientific notation for a large scale
+        assert_eq!(Decimal::new(1, 28).into_string(), "0.0000000000000000000000000001");
+    }
+
+    #[test]
+    fn max() {
+        assert_eq!(Decimal::MAX.into_string(), "79228162514264337593543950335");
+        assert_eq!(Decimal::MIN.into_string(), "-79228162514264337593543950335");
+        assert_eq!(decimal_into_string_normalized(&Decimal::MAX), "79228162514264337593543950335");
+    }
+}
@@ -0,0 +1,51 @@
+//! The canonical `Display` output, pre-release and build metadata included.
+
+use semver::{Version,VersionReq};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for Version {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for Version {}
+impl IntoStringBase for Version {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoString for VersionReq {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for VersionReq {}
+impl IntoStringBase for VersionReq {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions() {
+        assert_eq!(Version::new(1, 2, 3).into_string(), "1.2.3");
+        let version = Version::parse("1.0.0-alpha.1+build.5").unwrap();
+        assert_eq!((&version).into_string(), "1.0.0-alpha.1+build.5");
+        assert_eq!(version.into_string(), "1.0.0-alpha.1+build.5");
+    }
+
+    #[test]
+    fn requirements() {
+        let req = VersionReq::parse(">=1.2, <2.0").unwrap();
+        assert_eq!((&req).into_string(), ">=1.2, <2.0");
+        assert_eq!(req.into_string(), ">=1.2, <2.0");
+        assert_eq!(VersionReq::STAR.into_string(), "*");
+        assert_eq!(VersionReq::parse("1.2.3").unwrap().into_string(), "^1.2.3");
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Serialize,Serializer};
+
+use crate::AsLossyStr;
+
+/// Serializes the wrapped value as a string, with the crate's lossy rules.
+///
+/// Works for anything implementing [`AsLossyStr`], so `OsString`, `PathBuf`,
+/// `CString`, `String`, their borrowed forms and references to them.
+/// Valid text is passed to `serialize_str` as is, nothing is allocated.
+#[repr(transparent)]
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct LossyStr<T>(pub T);
+
+impl<T: AsLossyStr> Serialize for LossyStr<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.as_lossy_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        ffi::{CString,OsStr,OsString},
+        path::PathBuf,
+    };
+
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Entry<'a> {
+        name: LossyStr<OsString>,
+        path: LossyStr<&'a PathBuf>,
+        c: LossyStr<CString>,
+        text: LossyStr<Cow<'a,str>>,
+    }
+
+    #[test]
+    fn valid_fields() {
+        let path = PathBuf::from("dir/file");
+        let entry = Entry {
+            name: LossyStr(OsString::from("caf\u{e9}")),
+            path: LossyStr(&path),
+            c: LossyStr(CString::new("c").unwrap()),
+            text: LossyStr(Cow::Borrowed("\"quoted\"")),
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"name":"café","path":"dir/file","c":"c","text":"\"quoted\""}"#,
+        );
+        assert_eq!(serde_json::to_string(&LossyStr(OsStr::new("x"))).unwrap(), r#""x""#);
+    }
+
+    // a `CStr` with invalid utf8 has every byte replaced, the same as `into_string`
+    #[cfg(unix)]
+    #[test]
+    fn invalid_os_string_field() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let path = PathBuf::from(OsString::from_vec(b"/tmp/\xFF".to_vec()));
+        let entry = Entry {
+            name: LossyStr(OsString::from_vec(b"bad\xFFname".to_vec())),
+            path: LossyStr(&path),
+            c: LossyStr(CString::new(b"\xC3(".to_vec()).unwrap()),
+            text: LossyStr(Cow::Owned(String::new())),
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            "{\"name\":\"bad\u{FFFD}name\",\"path\":\"/tmp/\u{FFFD}\",\"c\":\"\u{FFFD}\u{FFFD}\",\"text\":\"\"}",
+        );
+    }
+}
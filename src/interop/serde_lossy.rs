@@ -0,0 +1,85 @@
+//! Lossy deserialization of `String` fields, for use with
+//! `#[serde(with = "to_string::serde_lossy")]`
+//!
+//! The field is read as bytes, so both strings and byte sequences are
+//! accepted and invalid utf8 is replaced with `U+FFFD` instead of being
+//! an error. The field is serialized as a normal string.
+
+use std::fmt;
+
+use serde::{
+    de::{Deserializer,Error,SeqAccess,Visitor},
+    Serializer,
+};
+
+use crate::{local_to_cow,local_vec_to_str};
+
+pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    deserializer.deserialize_bytes(LossyVisitor)
+}
+
+struct LossyVisitor;
+
+impl<'de> Visitor<'de> for LossyVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string or a sequence of bytes")
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+    fn visit_string<E: Error>(self, v: String) -> Result<String, E> {
+        Ok(v)
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<String, E> {
+        Ok(local_to_cow("[u8]", v).into_owned())
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<String, E> {
+        Ok(local_vec_to_str("Vec<u8>", v))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<String, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element::<u8>()? {
+            bytes.push(b);
+        }
+        Ok(local_vec_to_str("Vec<u8>", bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize,Serialize};
+
+    #[derive(Debug,Deserialize,Serialize,PartialEq)]
+    struct Message {
+        #[serde(with = "crate::serde_lossy")]
+        text: String,
+    }
+
+    fn message(text: &str) -> Message {
+        Message { text: text.to_string() }
+    }
+
+    #[test]
+    fn json_string_and_bytes() {
+        assert_eq!(serde_json::from_str::<Message>(r#"{"text":"café"}"#).unwrap(), message("café"));
+        let bytes = r#"{"text":[104,105,255,33]}"#;
+        assert_eq!(serde_json::from_str::<Message>(bytes).unwrap(), message("hi\u{FFFD}!"));
+        assert_eq!(serde_json::to_string(&message("hi")).unwrap(), r#"{"text":"hi"}"#);
+        assert!(serde_json::from_str::<Message>(r#"{"text":[256]}"#).is_err());
+    }
+
+    #[test]
+    fn postcard_invalid_bytes() {
+        // a length, then the raw bytes
+        let encoded = [5, b'a', 0xFF, b'b', 0xE2, 0x82];
+        assert_eq!(postcard::from_bytes::<Message>(&encoded).unwrap(), message("a\u{FFFD}b\u{FFFD}"));
+        let valid = postcard::to_allocvec(&message("héllo")).unwrap();
+        assert_eq!(postcard::from_bytes::<Message>(&valid).unwrap(), message("héllo"));
+    }
+}
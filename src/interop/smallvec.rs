@@ -0,0 +1,54 @@
+//! `SmallVec` of bytes, replaced like `String::from_utf8_lossy`. A spilled
+//! one gives its heap buffer to the `String` when it is valid utf8, an
+//! inline one is copied once.
+
+use smallvec::{Array,SmallVec};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_to_cow,local_vec_to_str};
+
+impl<A: Array<Item = u8>> IntoString for SmallVec<A> {
+    fn into_string(self) -> String {
+        // `into_vec` keeps the allocation of a spilled vector
+        local_vec_to_str("SmallVec", self.into_vec())
+    }
+}
+impl<A: Array<Item = u8>> Sealed for SmallVec<A> {}
+impl<A: Array<Item = u8>> IntoStringBase for SmallVec<A> {
+    fn ref_into_string(&self) -> String {
+        local_to_cow("SmallVec", self).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smallvec::smallvec;
+
+    use super::*;
+
+    #[test]
+    fn inline_valid() {
+        let bytes: SmallVec<[u8; 8]> = SmallVec::from_slice(b"inline");
+        assert!(!bytes.spilled());
+        assert_eq!((&bytes).into_string(), "inline");
+        assert_eq!(bytes.into_string(), "inline");
+    }
+
+    #[test]
+    fn spilled_keeps_the_buffer() {
+        let bytes: SmallVec<[u8; 4]> = SmallVec::from_slice(b"spilled onto the heap");
+        assert!(bytes.spilled());
+        let ptr = bytes.as_ptr();
+        let text = bytes.into_string();
+        assert_eq!(text, "spilled onto the heap");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn invalid() {
+        let bytes: SmallVec<[u8; 4]> = smallvec![b'a', 0xFF, b'b'];
+        assert_eq!((&bytes).into_string(), "a\u{FFFD}b");
+        assert_eq!(bytes.into_string(), "a\u{FFFD}b");
+        let bytes: SmallVec<[u8; 2]> = SmallVec::from_slice(b"spilled \xE2\x82");
+        assert_eq!(bytes.into_string(), "spilled \u{FFFD}");
+    }
+}
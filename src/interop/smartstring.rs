@@ -0,0 +1,68 @@
+use smartstring::{SmartString,SmartStringMode};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl<M: SmartStringMode> IntoString for SmartString<M> {
+    /// Special case, a boxed `SmartString` hands over its buffer
+    fn into_string(self) -> String {
+        String::from(self)
+    }
+}
+impl<M: SmartStringMode> Sealed for SmartString<M> {}
+impl<M: SmartStringMode> IntoStringBase for SmartString<M> {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// Converts _something_ into a
+/// [`SmartString`](https://docs.rs/smartstring/latest/smartstring/struct.SmartString.html)
+/// of either layout.
+///
+/// Implemented for everything which implements `IntoString`, the lossy
+/// conversion is the exact same. When the converted text is short enough
+/// it is stored inline rather than on the heap.
+pub trait IntoSmartString {
+    fn into_smart_string<M: SmartStringMode>(self) -> SmartString<M>;
+}
+
+impl<T: IntoString> IntoSmartString for T {
+    fn into_smart_string<M: SmartStringMode>(self) -> SmartString<M> {
+        SmartString::from(self.into_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::{OsStr,OsString};
+
+    use smartstring::{Compact,LazyCompact};
+
+    use super::*;
+
+    #[test]
+    fn both_layouts_convert() {
+        assert_eq!(SmartString::<Compact>::from("compact").into_string(), "compact");
+        assert_eq!(SmartString::<LazyCompact>::from("lazy").into_string(), "lazy");
+        assert_eq!((&SmartString::<Compact>::from("by ref")).into_string(), "by ref");
+    }
+
+    #[test]
+    fn short_output_is_inline() {
+        let compact: SmartString<Compact> = OsStr::new("short").into_smart_string();
+        let lazy: SmartString<LazyCompact> = OsStr::new("short").into_smart_string();
+        assert!(compact.is_inline());
+        assert!(lazy.is_inline());
+        assert_eq!(compact, "short");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_os_string() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let name = OsString::from_vec(b"bad\xFFname".to_vec());
+        let out: SmartString<Compact> = name.into_smart_string();
+        assert_eq!(out, "bad\u{FFFD}name");
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    ffi::{CString,OsString},
+    path::PathBuf,
+};
+
+use smol_str::SmolStr;
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,AsLossyStr,os::path_buf_to_str};
+
+impl IntoString for SmolStr {
+    fn into_string(self) -> String {
+        self.as_str().to_string()
+    }
+}
+impl Sealed for SmolStr {}
+impl IntoStringBase for SmolStr {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// Converts _something_ into a
+/// [`SmolStr`](https://docs.rs/smol_str/latest/smol_str/struct.SmolStr.html)
+///
+/// Borrowed sources (`&str`, `&String`, `&OsStr`, `&OsString`, `&CStr`,
+/// `&CString`, `&Path`, `&PathBuf`) which are valid utf8 are copied straight
+/// into the `SmolStr`, no intermediate `String` is built. Owned sources go
+/// through their `IntoString` impl. Text of 23 bytes or less is stored inline.
+pub trait IntoSmolStr {
+    fn into_smol_str(self) -> SmolStr;
+}
+
+impl<T: AsLossyStr + ?Sized> IntoSmolStr for &T {
+    fn into_smol_str(self) -> SmolStr {
+        SmolStr::from(self.as_lossy_str())
+    }
+}
+impl IntoSmolStr for String {
+    fn into_smol_str(self) -> SmolStr {
+        SmolStr::from(self)
+    }
+}
+impl IntoSmolStr for OsString {
+    fn into_smol_str(self) -> SmolStr {
+        SmolStr::from(IntoString::into_string(self))
+    }
+}
+impl IntoSmolStr for CString {
+    fn into_smol_str(self) -> SmolStr {
+        SmolStr::from(IntoString::into_string(self))
+    }
+}
+impl IntoSmolStr for PathBuf {
+    fn into_smol_str(self) -> SmolStr {
+        SmolStr::from(path_buf_to_str(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use super::*;
+
+    #[test]
+    fn smol_str_input() {
+        assert_eq!(SmolStr::new("text").into_string(), "text");
+        assert_eq!((&SmolStr::new("text")).into_string(), "text");
+    }
+
+    #[test]
+    fn short_valid_os_str_is_inline() {
+        // 23 bytes, the longest which fits inline
+        let out = OsStr::new("twenty-three bytes long").into_smol_str();
+        assert_eq!(out.len(), 23);
+        assert!(!out.is_heap_allocated());
+        assert!("a longer string which is put on the heap".into_smol_str().is_heap_allocated());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_sources() {
+        use std::{ffi::CString,os::unix::ffi::OsStringExt};
+
+        let name = OsString::from_vec(b"bad\xFFname".to_vec());
+        assert_eq!((&name).into_smol_str(), "bad\u{FFFD}name");
+        assert_eq!(name.into_smol_str(), "bad\u{FFFD}name");
+        let c = CString::new(b"\xFFx".to_vec()).unwrap();
+        assert_eq!(c.as_c_str().into_smol_str(), "\u{FFFD}\u{FFFD}");
+        assert_eq!(c.into_smol_str(), "\u{FFFD}\u{FFFD}");
+        assert_eq!(PathBuf::from(OsString::from_vec(b"p\xFF".to_vec())).into_smol_str(), "p\u{FFFD}");
+    }
+}
@@ -0,0 +1,111 @@
+//! Formatted as RFC 3339 with `format(&Rfc3339)`.
+//!
+//! `PrimitiveDateTime` has no offset, it is formatted as if it were UTC and
+//! the trailing `Z` is left off, `2024-03-01T12:30:00.25`. `Date` is the
+//! RFC 3339 `full-date`, `2024-03-01`.
+//!
+//! RFC 3339 can't represent everything `time` can (years past 9999, offsets
+//! with seconds), if formatting fails the value's `Display` output is used
+//! instead of panicking.
+
+use time::{
+    format_description::well_known::Rfc3339,
+    Date,OffsetDateTime,PrimitiveDateTime,
+};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+fn offset_date_time(x: &OffsetDateTime) -> String {
+    x.format(&Rfc3339).unwrap_or_else(|_| x.to_string())
+}
+
+fn primitive_date_time(x: &PrimitiveDateTime) -> String {
+    match x.assume_utc().format(&Rfc3339) {
+        Ok(mut s) => {
+            s.pop();
+            s
+        }
+        Err(_) => x.to_string(),
+    }
+}
+
+impl IntoString for OffsetDateTime {
+    fn into_string(self) -> String {
+        offset_date_time(&self)
+    }
+}
+impl Sealed for OffsetDateTime {}
+impl IntoStringBase for OffsetDateTime {
+    fn ref_into_string(&self) -> String {
+        offset_date_time(self)
+    }
+}
+
+impl IntoString for PrimitiveDateTime {
+    fn into_string(self) -> String {
+        primitive_date_time(&self)
+    }
+}
+impl Sealed for PrimitiveDateTime {}
+impl IntoStringBase for PrimitiveDateTime {
+    fn ref_into_string(&self) -> String {
+        primitive_date_time(self)
+    }
+}
+
+impl IntoString for Date {
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for Date {}
+impl IntoStringBase for Date {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Month,Time,UtcOffset};
+
+    use super::*;
+
+    fn at(y: i32, m: Month, d: u8, time: Time) -> PrimitiveDateTime {
+        PrimitiveDateTime::new(Date::from_calendar_date(y, m, d).unwrap(), time)
+    }
+
+    #[test]
+    fn utc() {
+        let x = at(2024, Month::March, 1, Time::from_hms(12, 30, 0).unwrap()).assume_utc();
+        assert_eq!(x.into_string(), "2024-03-01T12:30:00Z");
+        assert_eq!((&x).into_string(), "2024-03-01T12:30:00Z");
+    }
+
+    #[test]
+    fn negative_offset() {
+        let offset = UtcOffset::from_hms(-3, -30, 0).unwrap();
+        let x = at(2024, Month::March, 1, Time::from_hms_milli(8, 0, 0, 250).unwrap()).assume_offset(offset);
+        assert_eq!(x.into_string(), "2024-03-01T08:00:00.25-03:30");
+    }
+
+    #[test]
+    fn before_a_leap_second() {
+        // 2016-12-31 ended with 23:59:60, which `time` can't represent
+        let time = Time::from_hms_nano(23, 59, 59, 999_999_999).unwrap();
+        let x = at(2016, Month::December, 31, time);
+        assert_eq!(x.assume_utc().into_string(), "2016-12-31T23:59:59.999999999Z");
+        assert_eq!(x.into_string(), "2016-12-31T23:59:59.999999999");
+        assert_eq!(x.date().into_string(), "2016-12-31");
+        assert_eq!((&x.date()).into_string(), "2016-12-31");
+    }
+
+    #[test]
+    fn display_when_rfc3339_fails() {
+        // RFC 3339 has no seconds in the offset
+        let offset = UtcOffset::from_hms(1, 0, 30).unwrap();
+        let x = at(2024, Month::March, 1, Time::MIDNIGHT).assume_offset(offset);
+        assert!(x.format(&Rfc3339).is_err());
+        assert_eq!(x.into_string(), x.to_string());
+    }
+}
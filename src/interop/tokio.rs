@@ -0,0 +1,81 @@
+//! The async counterpart of [`io::read_to_string_lossy`](crate::io::read_to_string_lossy)
+//! for tokio's `AsyncRead`.
+
+use std::{future::Future,io};
+
+use tokio::io::{AsyncRead,AsyncReadExt};
+
+use crate::local_vec_to_str;
+
+/// Reads everything from `reader` into a `String`.
+///
+/// The same as [`io::read_to_string_lossy`](crate::io::read_to_string_lossy):
+/// bad sequences are replaced with `U+FFFD`, and when everything read is
+/// valid utf8 the buffer is returned as is, without a second copy.
+///
+/// If an IO error happens part way through, the error is returned and
+/// anything read up to that point is discarded.
+///
+/// # Cancel safety
+///
+/// Not cancel safe, dropping the future drops whatever was read so far.
+pub async fn read_to_string_lossy_async<R: AsyncRead + Unpin>(mut reader: R) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).await?;
+    Ok(local_vec_to_str("AsyncRead", buffer))
+}
+
+/// Extension trait for tokio's `AsyncRead`
+pub trait AsyncReadLossyExt: AsyncRead + Unpin {
+    /// Same as [`read_to_string_lossy_async`]
+    fn lossy_string(&mut self) -> impl Future<Output = io::Result<String>> + Send + '_
+    where
+        Self: Send,
+    {
+        read_to_string_lossy_async(self)
+    }
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> AsyncReadLossyExt for R { }
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncWriteExt;
+    use tokio_test::io::Builder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn valid_and_invalid() {
+        let text = read_to_string_lossy_async(&b"h\xC3\xA9llo"[..]).await.unwrap();
+        assert_eq!(text, "héllo");
+        let text = (&mut &b"a\xFFb"[..]).lossy_string().await.unwrap();
+        assert_eq!(text, "a\u{FFFD}b");
+    }
+
+    #[tokio::test]
+    async fn split_between_reads() {
+        // the emoji is cut in two, then an invalid byte ends a read
+        let mut reader = Builder::new().read(b"a\xF0\x9F").read(b"\x98\x80b\xE2").read(b"c").build();
+        assert_eq!(reader.lossy_string().await.unwrap(), "a😀b\u{FFFD}c");
+    }
+
+    #[tokio::test]
+    async fn duplex() {
+        let (mut client, server) = tokio::io::duplex(4);
+        let writer = async move {
+            for chunk in [&b"ca"[..], b"f\xC3", b"\xA9 \xFF"] {
+                client.write_all(chunk).await.unwrap();
+            }
+        };
+        let (_, text) = tokio::join!(writer, read_to_string_lossy_async(server));
+        assert_eq!(text.unwrap(), "café \u{FFFD}");
+    }
+
+    #[tokio::test]
+    async fn errors_are_returned() {
+        let reader = Builder::new().read(b"partial").read_error(io::Error::other("reset")).build();
+        let err = read_to_string_lossy_async(reader).await.unwrap_err();
+        assert_eq!(err.to_string(), "reset");
+    }
+}
@@ -0,0 +1,60 @@
+//! A `tracing` event for lossy conversions, so they show up in the logs.
+//!
+//! Every conversion which replaces something emits a `WARN` event with the
+//! target `to_string::lossy` and the fields
+//!
+//! * `source`: the type which was converted, `"OsString"`, `"CStr"`, ...
+//! * `invalid_bytes`: how many bytes of the input were not utf8
+//! * `preview`: the start of the input, escaped like `b"..."` and cut off
+//!   after 64 bytes
+//!
+//! Only the first [`DEFAULT_LOSSY_WARNING_LIMIT`] events are emitted, so a
+//! hot loop over bad input can not flood the logs, see
+//! [`set_lossy_warning_limit`]. Conversions nobody listens for (no
+//! subscriber, or the target filtered out) do not count towards it.
+
+use core::sync::atomic::{AtomicU64,Ordering};
+
+use tracing::Level;
+
+/// How many events are emitted before they stop, unless changed with
+/// [`set_lossy_warning_limit`]
+pub const DEFAULT_LOSSY_WARNING_LIMIT: u64 = 100;
+
+// bytes of input in the preview
+const PREVIEW_LEN: usize = 64;
+
+static LIMIT: AtomicU64 = AtomicU64::new(DEFAULT_LOSSY_WARNING_LIMIT);
+static EMITTED: AtomicU64 = AtomicU64::new(0);
+
+/// Sets how many lossy conversions in the whole process get an event.
+///
+/// The count starts again from zero, so this also turns the events back
+/// on after the limit was reached. `0` turns them off and `u64::MAX` never
+/// stops them.
+pub fn set_lossy_warning_limit(limit: u64) {
+    LIMIT.store(limit, Ordering::Relaxed);
+    EMITTED.store(0, Ordering::Relaxed);
+}
+
+// called by every conversion which is about to replace something in `x`
+pub(crate) fn warn_lossy(source: &'static str, x: &[u8]) {
+    if !tracing::enabled!(target: "to_string::lossy", Level::WARN) {
+        return;
+    }
+    if EMITTED.fetch_add(1, Ordering::Relaxed) >= LIMIT.load(Ordering::Relaxed) {
+        return;
+    }
+    let invalid_bytes: usize = x.utf8_chunks().map(|x| x.invalid().len()).sum();
+    let preview = match x.get(..PREVIEW_LEN) {
+        Some(start) if start.len() < x.len() => format!("{}...", start.escape_ascii()),
+        _ => x.escape_ascii().to_string(),
+    };
+    tracing::warn!(
+        target: "to_string::lossy",
+        source,
+        invalid_bytes,
+        preview = preview.as_str(),
+        "lossy conversion replaced invalid utf8"
+    );
+}
@@ -0,0 +1,61 @@
+use url::{Position,Url};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,percent_decode_into_string};
+
+impl IntoString for Url {
+    /// Special case, the `Url` is already a `String` so it is handed over
+    fn into_string(self) -> String {
+        String::from(self)
+    }
+}
+impl Sealed for Url {}
+impl IntoStringBase for Url {
+    fn ref_into_string(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+/// The `Url` for showing to a human, with the path and query percent-decoded.
+///
+/// `https://example.com/caf%C3%A9?q=%C3%A9` becomes `https://example.com/café?q=é`.
+/// Decoded bytes which aren't valid utf8 are replaced with `U+FFFD`.
+/// Everything else, including the fragment, is left as is. The output is
+/// not guaranteed to parse back into the same `Url`.
+pub fn url_into_decoded_string(url: &Url) -> String {
+    let mut out = String::with_capacity(url.as_str().len());
+    out.push_str(&url[..Position::BeforePath]);
+    out.push_str(&percent_decode_into_string(url.path()));
+    if let Some(query) = url.query() {
+        out.push('?');
+        out.push_str(&percent_decode_into_string(query));
+    }
+    out.push_str(&url[Position::AfterQuery..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoded_path_and_query() {
+        let url = Url::parse("https://example.com/caf%C3%A9?q=%C3%A9#caf%C3%A9").unwrap();
+        assert_eq!(url_into_decoded_string(&url), "https://example.com/café?q=é#caf%C3%A9");
+        assert_eq!((&url).into_string(), "https://example.com/caf%C3%A9?q=%C3%A9#caf%C3%A9");
+    }
+
+    #[test]
+    fn invalid_decoded_bytes() {
+        let url = Url::parse("https://user@example.com:8080/a%FFb?x=%C3").unwrap();
+        assert_eq!(url_into_decoded_string(&url), "https://user@example.com:8080/a\u{FFFD}b?x=\u{FFFD}");
+    }
+
+    #[test]
+    fn consuming_keeps_the_buffer() {
+        let url = Url::parse("https://example.com/path").unwrap();
+        let ptr = url.as_str().as_ptr();
+        let text = IntoString::into_string(url);
+        assert_eq!(text, "https://example.com/path");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,56 @@
+use uuid::Uuid;
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for Uuid {
+    /// The hyphenated lowercase form, `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    fn into_string(self) -> String {
+        self.hyphenated().encode_lower(&mut Uuid::encode_buffer()).to_string()
+    }
+}
+impl Sealed for Uuid {}
+impl IntoStringBase for Uuid {
+    fn ref_into_string(&self) -> String {
+        self.hyphenated().encode_lower(&mut Uuid::encode_buffer()).to_string()
+    }
+}
+
+/// The simple lowercase form, `67e5504410b1426f9247bb680e5fe0c8`
+pub fn uuid_into_simple_string(uuid: &Uuid) -> String {
+    uuid.simple().encode_lower(&mut Uuid::encode_buffer()).to_string()
+}
+
+/// The urn form, `urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8`
+pub fn uuid_into_urn_string(uuid: &Uuid) -> String {
+    uuid.urn().encode_lower(&mut Uuid::encode_buffer()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_display() {
+        // xorshift, random enough without a source of randomness
+        let mut state = 0x2545_F491_4F6C_DD1D_u128;
+        for _ in 0..64 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let uuid = Uuid::from_u128(state);
+            assert_eq!(uuid.into_string(), uuid.hyphenated().to_string());
+            assert_eq!((&uuid).into_string(), uuid.hyphenated().to_string());
+            assert_eq!(uuid_into_simple_string(&uuid), uuid.simple().to_string());
+            assert_eq!(uuid_into_urn_string(&uuid), uuid.urn().to_string());
+        }
+    }
+
+    #[test]
+    fn nil_and_max() {
+        assert_eq!(Uuid::nil().into_string(), "00000000-0000-0000-0000-000000000000");
+        assert_eq!(uuid_into_simple_string(&Uuid::nil()), "00000000000000000000000000000000");
+        let max = Uuid::from_u128(u128::MAX);
+        assert_eq!(max.into_string(), "ffffffff-ffff-ffff-ffff-ffffffffffff");
+        assert_eq!(uuid_into_urn_string(&max), "urn:uuid:ffffffff-ffff-ffff-ffff-ffffffffffff");
+    }
+}
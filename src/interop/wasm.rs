@@ -0,0 +1,98 @@
+//! JS strings are utf16 and may hold lone surrogates. Strings are copied
+//! out through wasm-bindgen, which encodes them with `TextEncoder` and so
+//! turns each lone surrogate into `U+FFFD`, the same as `into_string`
+//! does for any other utf16.
+
+use js_sys::{Function,JsString,Object,Reflect};
+use wasm_bindgen::{JsCast,JsValue};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed};
+
+impl IntoString for JsString {
+    fn into_string(self) -> String {
+        js_string_to_str(&self)
+    }
+}
+impl Sealed for JsString {}
+impl IntoStringBase for JsString {
+    fn ref_into_string(&self) -> String {
+        js_string_to_str(self)
+    }
+}
+
+/// Converts any `JsValue` into a `String`.
+///
+/// JS strings are converted like `JsString`. `null` and `undefined`
+/// become `"null"` and `"undefined"`, anything else is the result of
+/// calling its JS `toString()` (so `42` becomes `"42"`). An object
+/// whose `toString` is missing (e.g. made by `Object.create(null)`), is
+/// not a function, throws or does not return a string is
+/// [`JS_VALUE_FALLBACK`], this never throws.
+pub fn js_value_into_string(value: &JsValue) -> String {
+    if let Some(s) = value.as_string() {
+        return s;
+    }
+    if value.is_null() {
+        return String::from("null");
+    }
+    if value.is_undefined() {
+        return String::from("undefined");
+    }
+    if !value.is_object() && !value.is_function() {
+        // numbers, booleans, bigints and symbols, whose `toString` can't
+        // be taken away
+        return js_string_to_str(&value.unchecked_ref::<Object>().to_string());
+    }
+    Reflect::get(value, &JsValue::from_str("toString"))
+        .ok()
+        .and_then(|f| f.dyn_into::<Function>().ok())
+        .and_then(|f| f.call0(value).ok())
+        .and_then(|s| s.as_string())
+        .unwrap_or_else(|| String::from(JS_VALUE_FALLBACK))
+}
+
+/// What [`js_value_into_string`] gives for an object it can't call
+/// `toString` on
+pub const JS_VALUE_FALLBACK: &str = "[object]";
+
+fn js_string_to_str(s: &JsString) -> String {
+    String::from(s)
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn strings() {
+        let s = JsString::from("héllo 😀");
+        assert_eq!((&s).into_string(), "héllo 😀");
+        assert_eq!(s.into_string(), "héllo 😀");
+        assert_eq!(js_value_into_string(&JsValue::from_str("text")), "text");
+    }
+
+    #[wasm_bindgen_test]
+    fn lone_surrogate() {
+        let s = JsString::from_char_code1(0xD800).concat(&JsValue::from_str("x"));
+        assert_eq!(js_value_into_string(&s), "\u{FFFD}x");
+        assert_eq!(s.into_string(), "\u{FFFD}x");
+    }
+
+    #[wasm_bindgen_test]
+    fn other_values() {
+        assert_eq!(js_value_into_string(&JsValue::from_f64(42.0)), "42");
+        assert_eq!(js_value_into_string(&JsValue::from_f64(1.5)), "1.5");
+        assert_eq!(js_value_into_string(&JsValue::TRUE), "true");
+        assert_eq!(js_value_into_string(&JsValue::NULL), "null");
+        assert_eq!(js_value_into_string(&JsValue::UNDEFINED), "undefined");
+        assert_eq!(js_value_into_string(&Object::new()), "[object Object]");
+    }
+
+    #[wasm_bindgen_test]
+    fn object_without_to_string() {
+        let bare: Object = Object::create(JsValue::NULL.unchecked_ref::<Object>());
+        assert_eq!(js_value_into_string(&bare), JS_VALUE_FALLBACK);
+    }
+}
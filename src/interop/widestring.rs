@@ -0,0 +1,86 @@
+//! utf16 is decoded with each unpaired surrogate becoming `U+FFFD`,
+//! utf32 values which are surrogates or past `char::MAX` become `U+FFFD`.
+
+use widestring::{U16CStr,U16CString,U16Str,U16String,U32Str,U32String};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_wide_to_str,local_wide32_to_str};
+
+impl IntoString for U16CString {
+    fn into_string(self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+impl Sealed for U16CString {}
+impl IntoStringBase for U16CString {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+
+impl Sealed for U16CStr {}
+impl IntoStringBase for U16CStr {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+
+impl IntoString for U16String {
+    fn into_string(self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+impl Sealed for U16String {}
+impl IntoStringBase for U16String {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+
+impl Sealed for U16Str {}
+impl IntoStringBase for U16Str {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(self.as_slice())
+    }
+}
+
+impl IntoString for U32String {
+    fn into_string(self) -> String {
+        local_wide32_to_str(self.as_slice())
+    }
+}
+impl Sealed for U32String {}
+impl IntoStringBase for U32String {
+    fn ref_into_string(&self) -> String {
+        local_wide32_to_str(self.as_slice())
+    }
+}
+
+impl Sealed for U32Str {}
+impl IntoStringBase for U32Str {
+    fn ref_into_string(&self) -> String {
+        local_wide32_to_str(self.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16_with_lone_surrogates() {
+        let units = [0x61, 0xD800, 0x62, 0xDC00, 0xD83D, 0xDE00];
+        let expected = String::from_utf16_lossy(&units);
+        assert_eq!(expected, "a\u{FFFD}b\u{FFFD}😀");
+        assert_eq!(U16String::from_vec(units).into_string(), expected);
+        assert_eq!(U16Str::from_slice(&units).into_string(), expected);
+        assert_eq!(U16CString::from_vec(units).unwrap().into_string(), expected);
+        assert_eq!(U16CString::from_vec(units).unwrap().as_ucstr().into_string(), expected);
+    }
+
+    #[test]
+    fn utf32_out_of_range() {
+        let values = [0x61, 0xD800, 0x11_0000, 0xFFFF_FFFF, 0x1F600];
+        assert_eq!(U32String::from_vec(values).into_string(), "a\u{FFFD}\u{FFFD}\u{FFFD}😀");
+        assert_eq!(U32Str::from_slice(&values).into_string(), "a\u{FFFD}\u{FFFD}\u{FFFD}😀");
+    }
+}
@@ -0,0 +1,99 @@
+//! `HSTRING` and `BSTR` deref to utf16 which may contain unpaired surrogates,
+//! each of those becomes `U+FFFD`. An empty (null) one becomes `""`.
+//!
+//! The types come from `windows-strings`, which the `windows` crate
+//! re-exports as `windows::core`.
+
+use windows_strings::{BSTR,HSTRING,PCWSTR,PWSTR};
+
+use crate::{IntoString,IntoStringBase,sealed::Sealed,local_wide_to_str};
+
+impl IntoString for HSTRING {
+    fn into_string(self) -> String {
+        local_wide_to_str(&self[..])
+    }
+}
+impl Sealed for HSTRING {}
+impl IntoStringBase for HSTRING {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(&self[..])
+    }
+}
+
+impl IntoString for BSTR {
+    fn into_string(self) -> String {
+        local_wide_to_str(&self[..])
+    }
+}
+impl Sealed for BSTR {}
+impl IntoStringBase for BSTR {
+    fn ref_into_string(&self) -> String {
+        local_wide_to_str(&self[..])
+    }
+}
+
+/// Converts the NUL terminated utf16 behind a `PCWSTR` into a `String`.
+///
+/// A null pointer becomes `""`, the same as
+/// [`wide_ptr_into_string_or_default`](crate::ffi::wide_ptr_into_string_or_default).
+///
+/// # Safety
+///
+/// When not null `ptr` must point to a NUL terminated utf16 string
+/// which stays valid and unchanged for the duration of the call.
+pub unsafe fn pcwstr_into_string(ptr: PCWSTR) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    // SAFETY: not null, the rest is up to the caller
+    local_wide_to_str(unsafe { ptr.as_wide() })
+}
+
+/// Converts the NUL terminated utf16 behind a `PWSTR` into a `String`.
+///
+/// A null pointer becomes `""`.
+///
+/// # Safety
+///
+/// Same as [`pcwstr_into_string`].
+pub unsafe fn pwstr_into_string(ptr: PWSTR) -> String {
+    // SAFETY: same contract
+    unsafe { pcwstr_into_string(PCWSTR(ptr.0)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `a`, an unpaired high surrogate, `b`, then a pair (`😀`)
+    const WIDE: &[u16] = &[0x61, 0xD800, 0x62, 0xD83D, 0xDE00];
+
+    #[test]
+    fn hstring_and_bstr() {
+        let h = HSTRING::from_wide(WIDE);
+        assert_eq!((&h).into_string(), "a\u{FFFD}b😀");
+        assert_eq!(h.into_string(), "a\u{FFFD}b😀");
+        let b = BSTR::from_wide(&[0xDC00, 0x63]);
+        assert_eq!((&b).into_string(), "\u{FFFD}c");
+        assert_eq!(b.into_string(), "\u{FFFD}c");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(HSTRING::new().into_string(), "");
+        assert_eq!(BSTR::new().into_string(), "");
+        assert_eq!(HSTRING::from_wide(&[]).into_string(), "");
+    }
+
+    #[test]
+    fn pointers() {
+        let mut wide = WIDE.to_vec();
+        wide.push(0);
+        unsafe {
+            assert_eq!(pcwstr_into_string(PCWSTR(wide.as_ptr())), "a\u{FFFD}b😀");
+            assert_eq!(pwstr_into_string(PWSTR(wide.as_mut_ptr())), "a\u{FFFD}b😀");
+            assert_eq!(pcwstr_into_string(PCWSTR::null()), "");
+            assert_eq!(pwstr_into_string(PWSTR::null()), "");
+        }
+    }
+}
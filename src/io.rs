@@ -0,0 +1,305 @@
+//! Lossy versions of the string returning methods in
+//! [`std::io`](https://doc.rust-lang.org/std/io/index.html).
+
+use std::io::{self,BufRead,ErrorKind,Read,Write};
+
+use super::{IntoString,IntoStringBase,IntoStringBomAware,LossyDecoder,local_vec_to_str,sealed::Sealed};
+
+/// Reads everything from `reader` into a `String`.
+///
+/// Unlike [`Read::read_to_string`](https://doc.rust-lang.org/std/io/trait.Read.html#method.read_to_string)
+/// invalid utf8 does not end the read, bad sequences are replaced
+/// with `U+FFFD`. When everything read is valid utf8 the buffer is
+/// returned as is, without a second copy.
+///
+/// If an IO error happens part way through, the error is returned and
+/// anything read up to that point is discarded.
+pub fn read_to_string_lossy<R: Read>(mut reader: R) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    Ok(local_vec_to_str("Read", buffer))
+}
+
+/// Reads everything from `reader` into a `String`, decoding it as UTF-16
+/// when it starts with a UTF-16 byte order mark.
+///
+/// The same as [`read_to_string_lossy`] otherwise, see
+/// [`bytes_into_string_bom_aware`](crate::bytes_into_string_bom_aware)
+/// for how the BOM is handled.
+pub fn read_to_string_bom_aware<R: Read>(mut reader: R) -> io::Result<String> {
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+    Ok(buffer.into_string_bom_aware())
+}
+
+impl IntoString for ErrorKind {
+    /// The text std uses when it displays an `io::Error` of this kind,
+    /// `NotFound` is `entity not found`, rather than the variant name.
+    ///
+    /// This is std's own `Display` for `ErrorKind`, so it stays in sync as
+    /// variants are added.
+    fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+impl Sealed for ErrorKind {}
+impl IntoStringBase for ErrorKind {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Extension trait for [`Read`](https://doc.rust-lang.org/std/io/trait.Read.html)
+pub trait ReadExt: Read {
+    /// Same as [`read_to_string_lossy`]
+    fn lossy_string(&mut self) -> io::Result<String> {
+        read_to_string_lossy(self)
+    }
+}
+
+impl<R: Read + ?Sized> ReadExt for R { }
+
+/// Iterator over the lines of a `BufRead`, converted lossily.
+///
+/// The lossy counterpart of [`std::io::Lines`](https://doc.rust-lang.org/std/io/struct.Lines.html),
+/// created by [`BufReadExt::lossy_lines`]. Each line has its trailing `\n`
+/// (or `\r\n`) removed, and a last line without a newline is still returned.
+/// IO errors are passed through.
+#[derive(Debug)]
+pub struct LossyLines<R> {
+    reader: R,
+}
+
+impl<R: BufRead> Iterator for LossyLines<R> {
+    type Item = io::Result<String>;
+    fn next(&mut self) -> Option<io::Result<String>> {
+        read_line_lossy_from(&mut self.reader).transpose()
+    }
+}
+
+/// Reads one line from stdin.
+///
+/// The line terminator is removed and invalid utf8 is replaced with
+/// `U+FFFD`. At the end of input an empty `String` is returned, use
+/// [`read_line_lossy_from`] with `stdin().lock()` to tell the two apart.
+pub fn read_line_lossy() -> io::Result<String> {
+    read_line_lossy_from(&mut io::stdin().lock()).map(Option::unwrap_or_default)
+}
+
+/// Reads one line from `reader`, returns `None` at the end of input.
+///
+/// The trailing `\n` (or `\r\n`) is removed, a last line without a
+/// newline is still returned.
+pub fn read_line_lossy_from<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut buffer = Vec::new();
+    if reader.read_until(b'\n', &mut buffer)? == 0 {
+        return Ok(None);
+    }
+    if buffer.last() == Some(&b'\n') {
+        buffer.pop();
+        if buffer.last() == Some(&b'\r') {
+            buffer.pop();
+        }
+    }
+    Ok(Some(local_vec_to_str("BufRead", buffer)))
+}
+
+/// Extension trait for [`BufRead`](https://doc.rust-lang.org/std/io/trait.BufRead.html)
+pub trait BufReadExt: BufRead {
+    /// Same as `BufRead::lines` but invalid utf8 is replaced instead of
+    /// returned as an error, see [`LossyLines`]
+    fn lossy_lines(self) -> LossyLines<Self>
+    where
+        Self: Sized,
+    {
+        LossyLines { reader: self }
+    }
+}
+
+impl<R: BufRead + ?Sized> BufReadExt for R { }
+
+/// A `Write` sink that collects everything written to it into a `String`.
+///
+/// By default the bytes are kept as is and converted once by
+/// [`LossyStringWriter::into_string`]. The [`LossyStringWriter::incremental`]
+/// mode converts on every `write` instead, holding back at most 3 bytes when
+/// a multi-byte sequence is split between two writes. Both produce the same
+/// text as a single `String::from_utf8_lossy` over everything written.
+#[derive(Clone,Debug,Default)]
+pub struct LossyStringWriter {
+    mode: Mode,
+}
+
+#[derive(Clone,Debug)]
+enum Mode {
+    Buffered(Vec<u8>),
+    Incremental(LossyDecoder,String),
+}
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Buffered(Vec::new())
+    }
+}
+
+impl LossyStringWriter {
+
+    /// Collects raw bytes, converting them once at the end
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts as it goes, see the type level docs
+    pub fn incremental() -> Self {
+        LossyStringWriter {
+            mode: Mode::Incremental(LossyDecoder::new(), String::new()),
+        }
+    }
+
+    /// Returns everything written so far.
+    ///
+    /// In the incremental mode a sequence left incomplete by the last
+    /// write becomes a single `U+FFFD`.
+    pub fn into_string(self) -> String {
+        match self.mode {
+            Mode::Buffered(buffer) => local_vec_to_str("LossyStringWriter", buffer),
+            Mode::Incremental(decoder,mut text) => {
+                decoder.finish_to(&mut text);
+                text
+            }
+        }
+    }
+}
+
+impl Write for LossyStringWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.mode {
+            Mode::Buffered(buffer) => buffer.extend_from_slice(buf),
+            Mode::Incremental(decoder,text) => decoder.push_to(buf, text),
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    // hands out at most `chunk` bytes per `read`, then fails when `fail`
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+        fail: bool,
+    }
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() && self.fail {
+                return Err(io::Error::other("broken pipe"));
+            }
+            let n = self.data.len().min(self.chunk).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn read_valid_and_invalid() {
+        assert_eq!(read_to_string_lossy(Cursor::new("héllo")).unwrap(), "héllo");
+        assert_eq!(read_to_string_lossy(Cursor::new(b"a\xFFb")).unwrap(), "a\u{FFFD}b");
+        assert_eq!(Cursor::new(b"").lossy_string().unwrap(), "");
+    }
+
+    #[test]
+    fn read_in_chunks() {
+        // the `é` and the emoji are split between reads
+        let data = "é😀x".as_bytes();
+        let mut reader = Chunked { data, chunk: 1, fail: false };
+        assert_eq!(reader.lossy_string().unwrap(), "é😀x");
+        let reader = Chunked { data: b"ab\xF0\x9F\x98", chunk: 2, fail: false };
+        assert_eq!(read_to_string_lossy(reader).unwrap(), "ab\u{FFFD}");
+    }
+
+    #[test]
+    fn read_error_is_returned() {
+        let reader = Chunked { data: b"partial", chunk: 3, fail: true };
+        assert_eq!(read_to_string_lossy(reader).unwrap_err().to_string(), "broken pipe");
+    }
+
+    #[test]
+    fn lossy_lines_mixed_input() {
+        let input = Cursor::new(b"one\r\nt\xFFo\n\nlast".to_vec());
+        let lines: Vec<String> = input.lossy_lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one", "t\u{FFFD}o", "", "last"]);
+    }
+
+    #[test]
+    fn lossy_lines_passes_errors_through() {
+        let reader = io::BufReader::new(Chunked { data: b"ok\n", chunk: 8, fail: true });
+        let mut lines = reader.lossy_lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "ok");
+        assert!(lines.next().unwrap().is_err());
+    }
+
+    // writes `pieces` one `write` each, in both modes
+    fn write_pieces(pieces: &[&[u8]]) -> [String; 2] {
+        [LossyStringWriter::new(), LossyStringWriter::incremental()].map(|mut w| {
+            for piece in pieces {
+                assert_eq!(w.write(piece).unwrap(), piece.len());
+            }
+            w.into_string()
+        })
+    }
+
+    #[test]
+    fn writer_emoji_split_across_writes() {
+        let emoji = "😀".as_bytes();
+        assert_eq!(write_pieces(&[b"a", &emoji[..1], &emoji[1..], b"b"]), ["a😀b", "a😀b"]);
+        assert_eq!(write_pieces(&[&emoji[..2], &emoji[2..3], &emoji[3..]]), ["😀", "😀"]);
+    }
+
+    #[test]
+    fn writer_invalid_byte_at_chunk_boundary() {
+        assert_eq!(write_pieces(&[b"ab\xFF", b"\xFEcd"]), ["ab\u{FFFD}\u{FFFD}cd"; 2]);
+        // the start of a sequence which the next write does not finish
+        assert_eq!(write_pieces(&[b"a\xE2\x82", b"b"]), ["a\u{FFFD}b"; 2]);
+    }
+
+    #[test]
+    fn writer_incomplete_at_the_end() {
+        assert_eq!(write_pieces(&[b"a", b"\xF0\x9F"]), ["a\u{FFFD}"; 2]);
+        assert_eq!(write_pieces(&[]), [""; 2]);
+    }
+
+    #[test]
+    fn read_line_lossy_from_cursor() {
+        let mut input = Cursor::new(b"valid\nin\xFFvalid\r\nno newline".to_vec());
+        assert_eq!(read_line_lossy_from(&mut input).unwrap().as_deref(), Some("valid"));
+        assert_eq!(read_line_lossy_from(&mut input).unwrap().as_deref(), Some("in\u{FFFD}valid"));
+        assert_eq!(read_line_lossy_from(&mut input).unwrap().as_deref(), Some("no newline"));
+        assert_eq!(read_line_lossy_from(&mut input).unwrap(), None);
+        assert_eq!(read_line_lossy_from(&mut Cursor::new(b"")).unwrap(), None);
+    }
+
+    #[test]
+    fn error_kinds() {
+        assert_eq!(ErrorKind::NotFound.into_string(), "entity not found");
+        assert_eq!(ErrorKind::PermissionDenied.into_string(), "permission denied");
+        assert_eq!((&ErrorKind::UnexpectedEof).into_string(), "unexpected end of file");
+        assert_eq!(ErrorKind::Other.into_string(), "other error");
+        // the same text an error of that kind displays with
+        for kind in [ErrorKind::TimedOut, ErrorKind::InvalidData, ErrorKind::WouldBlock] {
+            assert_eq!(kind.into_string(), io::Error::from(kind).to_string());
+        }
+    }
+
+    #[test]
+    fn read_bom_aware() {
+        assert_eq!(read_to_string_bom_aware(Cursor::new(b"\xFF\xFEo\x00k\x00")).unwrap(), "ok");
+        assert_eq!(read_to_string_bom_aware(Cursor::new(b"\xEF\xBB\xBFok\xFF")).unwrap(), "ok\u{FFFD}");
+    }
+}
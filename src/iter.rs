@@ -0,0 +1,110 @@
+//! Converting every item of an iterator.
+
+use alloc::string::String;
+use core::iter::FusedIterator;
+
+use super::IntoString;
+
+/// Extension trait for iterators whose items convert.
+///
+/// `iter.map(|x| x.into_string())` does not compile for `CString` and
+/// `OsString` items, their own `into_string` (which fails on bad input)
+/// is picked over the one from [`IntoString`]. `map_into_string` always
+/// uses `IntoString`.
+pub trait IterIntoString: Iterator + Sized
+where
+    Self::Item: IntoString,
+{
+    /// Converts every item, see [`MapIntoString`]
+    fn map_into_string(self) -> MapIntoString<Self> {
+        MapIntoString { iter: self }
+    }
+}
+
+impl<I: Iterator> IterIntoString for I where I::Item: IntoString { }
+
+/// The iterator returned by [`IterIntoString::map_into_string`].
+///
+/// It has the same length as the iterator it wraps, and goes both ways
+/// when that does.
+#[derive(Clone,Debug)]
+pub struct MapIntoString<I> {
+    iter: I,
+}
+
+impl<I: Iterator> Iterator for MapIntoString<I>
+where
+    I::Item: IntoString,
+{
+    type Item = String;
+    fn next(&mut self) -> Option<String> {
+        self.iter.next().map(IntoString::into_string)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for MapIntoString<I>
+where
+    I::Item: IntoString,
+{
+    fn next_back(&mut self) -> Option<String> {
+        self.iter.next_back().map(IntoString::into_string)
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for MapIntoString<I> where I::Item: IntoString { }
+
+impl<I: FusedIterator> FusedIterator for MapIntoString<I> where I::Item: IntoString { }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        ffi::CString,
+        vec,
+        vec::Vec,
+    };
+
+    use super::*;
+
+    #[test]
+    fn c_strings() {
+        let items = vec![CString::new("a").unwrap(), CString::new(b"\xFF".to_vec()).unwrap(), CString::new("c").unwrap()];
+        let mut iter = items.into_iter().map_into_string();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back().as_deref(), Some("c"));
+        assert_eq!(iter.collect::<Vec<_>>(), ["a", "\u{FFFD}"]);
+    }
+
+    #[test]
+    fn borrowed_items() {
+        let items = [c"x", c"y"];
+        assert_eq!(items.into_iter().map_into_string().rev().collect::<Vec<_>>(), ["y", "x"]);
+        assert_eq!(items.into_iter().filter(|x| x.count_bytes() > 5).map_into_string().size_hint(), (0, Some(2)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_strings() {
+        use std::ffi::OsString;
+
+        let names = vec![OsString::from("one"), OsString::from("two")];
+        let ptr = names[1].as_encoded_bytes().as_ptr();
+        let strings: Vec<String> = names.into_iter().map_into_string().collect();
+        assert_eq!(strings, ["one", "two"]);
+        assert_eq!(strings[1].as_ptr(), ptr);
+    }
+
+    // like the file names from `fs::read_dir`
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn lossy_file_names() {
+        use std::{ffi::OsString,os::unix::ffi::OsStringExt};
+
+        let entries = [&b"ok.txt"[..], b"bad\xFF.txt"].into_iter().map(|x| Ok::<_, ()>(OsString::from_vec(x.to_vec())));
+        let names: Vec<String> = entries.map_while(Result::ok).map_into_string().collect();
+        assert_eq!(names, ["ok.txt", "bad\u{FFFD}.txt"]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Many values joined into one `String`.
+
+use alloc::string::String;
+
+use super::IntoString;
+
+/// Converts every key and value of `map` and joins them, `kv_sep` between
+/// a key and its value and `pair_sep` between the pairs.
+///
+/// `map_to_string(&vars, ", ", "=")` is `PATH=/usr/bin, HOME=/root`. The
+/// pairs are in the order `map` gives them, use a `BTreeMap` (or sort
+/// first) when the output has to be the same every time. Anything which
+/// iterates pairs works, `&HashMap<OsString, OsString>` included.
+pub fn map_to_string<K: IntoString, V: IntoString>(
+    map: impl IntoIterator<Item = (K, V)>,
+    pair_sep: &str,
+    kv_sep: &str,
+) -> String {
+    // the text of the pairs is only known once they are converted, so
+    // this guesses a short key and value for each on top of the separators
+    const PAIR_ESTIMATE: usize = 16;
+    let pairs = map.into_iter();
+    let (len, _) = pairs.size_hint();
+    let mut out = String::with_capacity(len.saturating_mul(pair_sep.len() + kv_sep.len() + PAIR_ESTIMATE));
+    for (i, (key, value)) in pairs.enumerate() {
+        if i > 0 {
+            out.push_str(pair_sep);
+        }
+        key.push_into(&mut out);
+        out.push_str(kv_sep);
+        value.push_into(&mut out);
+    }
+    out
+}
+
+/// Converted values joined into one `String`, to `collect` into.
+///
+/// `collect::<JoinedString>()` puts the values one after another, use
+/// [`with_sep`](JoinedString::with_sep) for a separator between them.
+#[derive(Clone,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct JoinedString(String);
+
+impl JoinedString {
+
+    /// Converts every value of `iter` and joins them with `sep` between
+    /// each one
+    pub fn with_sep<T: IntoString>(iter: impl IntoIterator<Item = T>, sep: &str) -> Self {
+        let mut out = String::new();
+        for (i, value) in iter.into_iter().enumerate() {
+            if i > 0 {
+                out.push_str(sep);
+            }
+            value.push_into(&mut out);
+        }
+        JoinedString(out)
+    }
+
+    /// The joined text
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The joined text
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<T: IntoString> FromIterator<T> for JoinedString {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::with_sep(iter, "")
+    }
+}
+
+impl From<JoinedString> for String {
+    fn from(value: JoinedString) -> String {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        collections::BTreeMap,
+        ffi::CString,
+        vec::Vec,
+    };
+    use core::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn map_of_c_strings() {
+        let mut map = BTreeMap::new();
+        map.insert("b", c"two");
+        map.insert("a", c"\xFF");
+        assert_eq!(map_to_string(map, ", ", "="), "a=\u{FFFD}, b=two");
+        assert_eq!(map_to_string(BTreeMap::<&str, &CStr>::new(), ", ", "="), "");
+        assert_eq!(map_to_string([("only", "one")], ", ", ": "), "only: one");
+        // room for the separators and a short key and value up front
+        let pairs = [("k", "v"); 8];
+        assert!(map_to_string(pairs, ", ", "=").capacity() >= 8 * (2 + 1 + 16));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn map_of_os_strings() {
+        use std::{
+            collections::HashMap,
+            ffi::{OsStr,OsString},
+        };
+
+        let mut map: BTreeMap<OsString, OsString> = BTreeMap::new();
+        map.insert("PATH".into(), "/usr/bin".into());
+        map.insert("HOME".into(), "/root".into());
+        assert_eq!(map_to_string(&map, ", ", "="), "HOME=/root, PATH=/usr/bin");
+        let single: HashMap<OsString, OsString> = [("K".into(), "V".into())].into();
+        assert_eq!(map_to_string(&single, ", ", "="), "K=V");
+        let borrowed = [(OsStr::new("A"), OsStr::new("1"))];
+        assert_eq!(map_to_string(borrowed, ", ", "="), "A=1");
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+
+            map.insert("BAD".into(), OsString::from_vec(b"a\xFF".to_vec()));
+            assert_eq!(map_to_string(map, "; ", "="), "BAD=a\u{FFFD}; HOME=/root; PATH=/usr/bin");
+        }
+    }
+
+    #[test]
+    fn joined_string_lengths() {
+        let none: [&str; 0] = [];
+        assert_eq!(none.into_iter().collect::<JoinedString>().into_inner(), "");
+        assert_eq!(JoinedString::with_sep(none, ", ").as_str(), "");
+        assert_eq!(JoinedString::with_sep([c"one"], ", ").as_str(), "one");
+        let many: Vec<CString> = [&b"a"[..], b"\xFF", b"c"].iter().map(|x| CString::new(*x).unwrap()).collect();
+        assert_eq!(JoinedString::with_sep(many.clone(), ", ").as_str(), "a, \u{FFFD}, c");
+        assert_eq!(String::from(many.into_iter().collect::<JoinedString>()), "a\u{FFFD}c");
+    }
+}
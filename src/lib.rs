@@ -1,12 +1,260 @@
 //! Note the conversions from OSString/OSStr will convert
 //! invalid utf-8 characters into the [`U+FFFD REPLACEMENT CHARACTER`](https://doc.rust-lang.org/std/char/constant.REPLACEMENT_CHARACTER.html)
 //! if they cannot convert.
+//!
+//! Integers can be written in any radix with [`IntoStringRadix`], and in
+//! hex with [`IntoHexString`].
+//!
+//! A `Duration` can be written for a person or as ISO 8601 with
+//! [`duration_to_string`], and a `SystemTime` as a Unix timestamp with
+//! [`system_time_epoch_string`].
+//!
+//! Types which only implement `Display` can go through
+//! [`display_to_string`] or the [`ViaDisplay`] wrapper.
+//!
+//! # Features
+//!
+//! Everything which needs another crate is behind a feature (mostly named
+//! after that crate). Apart from `std` and `deep-refs` all of them are off by default.
+//!
+//! * `deep-refs` (on by default): `&&T` and deeper references, at any
+//!   depth, for every `T` whose `&T` converts (see [`IntoStringBase`]).
+//!   `IntoStringRadix` goes up to `&&&&&&&&T`. Without it only `T` and
+//!   `&T` are covered, which is what most callers use. Code that only uses
+//!   one level of reference converts exactly the same either way; deeper
+//!   references need an explicit `*` first.
+//!   `cargo test --no-default-features --features std` checks the crate
+//!   without it.
+//! * `std` (on by default): the `OsStr`, `OsString` and `ExitStatus` impls,
+//!   [`with_scratch`], [`LossyOwned`], [`CommandStringExt`],
+//!   [`system_time_epoch_string`], and the `env`, `error`, `fs`, `hook`, `io` and
+//!   `path` modules. Without it the crate is `no_std` and only needs
+//!   `alloc`, which leaves `str`, `String`, `Cow<str>` and `CStr`. Every
+//!   feature except `arrayvec`, `heapless`, `human-size`, `metrics`,
+//!   `segmentation`, `simd` and `strict-debug` turns `std` back on. The
+//!   `tests/no_std` crate uses it from `no_std`, build it for a target
+//!   without `std` (`--target thumbv7em-none-eabihf`) to check.
+//! * `anyhow`: `anyhow::Error` with its chain, and [`anyhow_chain_string`] to
+//!   pick the [`error::Style`]
+//! * `arcstr`: `ArcStr`, and [`IntoArcStr`] to convert into one
+//! * `arrayvec`: [`IntoArrayString`] to convert into an `ArrayString` without
+//!   allocating
+//! * `ascii`: `AsciiString`, `AsciiStr`, and [`IntoAsciiStringLossy`] to
+//!   convert into an `AsciiString`
+//! * `beef`: `beef::Cow<str>`, `beef::lean::Cow<str>`
+//! * `bstr`: `BString`, `BStr`
+//! * `bytes`: `Bytes`, `BytesMut`, and [`buf_into_string`] for any `Buf`
+//! * `camino`: `Utf8PathBuf`, `Utf8Path`
+//! * `chrono`: `DateTime`, `NaiveDateTime` as RFC 3339
+//! * `core-foundation`: `CFString`, only on apple targets
+//! * `compact-str`: `CompactString`, and [`IntoCompactString`] to convert into one
+//! * `encoding`: decoding legacy encodings with `encoding_rs`, see [`bytes_into_string_with`]
+//! * `detect`: guessing the encoding with `chardetng`, see [`bytes_into_string_detected`]
+//! * `futures`: [`collect_string_lossy`] for a `Stream` of byte chunks
+//! * `heapless`: [`IntoHeaplessString`] to convert into a `heapless::String`
+//!   without allocating
+//! * `http`: `HeaderValue`, also as Latin-1, and [`header_map_into_strings`] for a
+//!   whole `HeaderMap`
+//! * `human-size`: [`bytes_human_string`] for byte counts like `1.5 MiB`,
+//!   and the [`HumanSize`] wrapper
+//! * `ipnet`: `IpNet`, `Ipv4Net`, `Ipv6Net`
+//! * `jni`: `JNIStr`, `JNIString`, `MUTF8Chars`, and [`modified_utf8_into_string`]
+//!   for the raw bytes of `GetStringUTFChars`
+//! * `metrics`: process wide counters of the conversions and how many of
+//!   them were lossy, see [`conversion_stats`]
+//! * `normalize`: unicode normalization with `unicode-normalization`, see [`Converter::nfc`]
+//! * `num-bigint`: `BigInt`, `BigUint`, also with [`IntoStringRadix`]
+//! * `objc2`: `NSString`, `Retained<NSString>`, only on apple targets
+//! * `os-str-bytes`: `RawOsString`, `RawOsStr`
+//! * `pyo3`: `Bound<PyString>`, and [`py_any_into_string`] for any Python object
+//! * `rayon`: [`par_into_strings`] and [`par_join_to_string`] to convert a
+//!   whole `Vec` in parallel
+//! * `rust-decimal`: `Decimal`, see [`decimal_into_string_normalized`] to drop
+//!   trailing zeros
+//! * `segmentation`: truncating on grapheme cluster boundaries with
+//!   `unicode-segmentation`, see [`Converter::max_graphemes`]
+//! * `semver`: `Version`, `VersionReq`
+//! * `serde`: [`LossyStr`] to serialize with the lossy rules, and [`serde_lossy`]
+//!   to deserialize `String` fields lossily
+//! * `simd`: validate utf8 with `simdutf8`, the output is identical
+//! * `smallvec`: `SmallVec` of bytes
+//! * `smartstring`: `SmartString`, and [`IntoSmartString`] to convert into one
+//! * `smol-str`: `SmolStr`, and [`IntoSmolStr`] to convert into one
+//! * `strict-debug`: any conversion of invalid utf8 panics, with the type
+//!   and the byte offset of the first bad sequence, rather than replacing
+//!   it. Only in builds with `debug_assertions`, release builds convert as
+//!   usual. UTF-16 and UTF-32 input is not checked.
+//! * `time`: `OffsetDateTime`, `PrimitiveDateTime`, `Date` as RFC 3339
+//! * `tokio`: [`read_to_string_lossy_async`] for any `AsyncRead`
+//! * `tracing`: a `WARN` event with the target `to_string::lossy` for every
+//!   lossy conversion, up to a limit, see [`set_lossy_warning_limit`]
+//! * `url`: `Url`, and [`url_into_decoded_string`] for showing one to a human
+//! * `uuid`: `Uuid` in the hyphenated form, see [`uuid_into_simple_string`] and
+//!   [`uuid_into_urn_string`] for the others
+//! * `wasm`: `JsString`, and [`js_value_into_string`] for any `JsValue`
+//! * `widestring`: `U16CString`, `U16CStr`, `U16String`, `U16Str`, `U32String`, `U32Str`
+//! * `windows`: `HSTRING`, `BSTR`, and `pcwstr_into_string`/`pwstr_into_string`
+//!   for raw pointers, only on windows targets
 
-use std::{
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
     borrow::Cow,
-    ffi::{OsStr,OsString,CStr,CString},
+    ffi::CString,
+    string::{String,ToString},
+    vec::Vec,
+};
+use core::ffi::CStr;
+
+use sealed::Sealed;
+
+#[macro_use]
+mod macros;
+
+mod convert;
+pub use convert::{Converter,IntoStringExt,Sanitize};
+
+#[cfg(any(feature = "arrayvec", feature = "heapless"))]
+mod fixed;
+
+mod decoder;
+pub use decoder::LossyDecoder;
+
+mod single_byte;
+pub use single_byte::{
+    Fallback,IntoStringCp1252,IntoStringLatin1,IntoStringMixed,
+    bytes_into_string_cp1252,bytes_into_string_latin1,bytes_into_string_mixed,
 };
 
+mod bom;
+pub use bom::{IntoStringBomAware,IntoStringNoBom,bytes_into_string_bom_aware,bytes_into_string_no_bom};
+
+mod radix;
+pub use radix::{IntoHexString,IntoStringRadix};
+
+mod duration;
+pub use duration::{DurationStyle,duration_to_string};
+
+#[cfg(feature = "human-size")]
+mod size;
+#[cfg(feature = "human-size")]
+pub use size::{HumanSize,SizeStyle,bytes_human_string};
+
+mod display;
+pub use display::{ViaDisplay,display_to_string};
+
+mod lossy;
+pub use lossy::Lossy;
+
+mod join;
+pub use join::{JoinedString,map_to_string};
+
+mod iter;
+pub use iter::{IterIntoString,MapIntoString};
+
+mod lines;
+pub use lines::{lossy_lines,lossy_lines_cow};
+
+mod percent;
+pub use percent::{percent_decode_into_string,percent_decode_bytes_into_string};
+#[cfg(feature = "std")]
+pub use percent::percent_decode_os_str_into_string;
+
+#[cfg(feature = "std")]
+mod os;
+
+#[cfg(feature = "std")]
+pub mod hook;
+
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::{ConversionStats,conversion_stats,reset_conversion_stats};
+
+#[cfg(feature = "std")]
+mod epoch;
+#[cfg(feature = "std")]
+pub use epoch::{EpochSecs,EpochUnit,system_time_epoch_string};
+
+#[cfg(feature = "std")]
+mod process;
+#[cfg(feature = "std")]
+pub use process::{CommandStringExt,CommandStrings};
+
+#[cfg(feature = "std")]
+mod lossy_owned;
+#[cfg(feature = "std")]
+pub use lossy_owned::LossyOwned;
+
+#[cfg(feature = "std")]
+mod scratch;
+#[cfg(feature = "std")]
+pub use scratch::with_scratch;
+
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "std")]
+pub mod error;
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod fs;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod path;
+
+mod interop;
+#[cfg(feature = "anyhow")]
+pub use interop::anyhow_chain_string;
+#[cfg(feature = "arcstr")]
+pub use interop::IntoArcStr;
+#[cfg(feature = "arrayvec")]
+pub use interop::{IntoArrayString,Overflow};
+#[cfg(feature = "ascii")]
+pub use interop::IntoAsciiStringLossy;
+#[cfg(feature = "bytes")]
+pub use interop::buf_into_string;
+#[cfg(feature = "compact-str")]
+pub use interop::IntoCompactString;
+#[cfg(feature = "detect")]
+pub use interop::bytes_into_string_detected;
+#[cfg(feature = "encoding")]
+pub use interop::{IntoStringWithEncoding,bytes_into_string_with};
+#[cfg(feature = "futures")]
+pub use interop::collect_string_lossy;
+#[cfg(feature = "heapless")]
+pub use interop::IntoHeaplessString;
+#[cfg(feature = "http")]
+pub use interop::{header_map_into_strings,header_map_into_strings_latin1};
+#[cfg(feature = "jni")]
+pub use interop::modified_utf8_into_string;
+#[cfg(feature = "pyo3")]
+pub use interop::py_any_into_string;
+#[cfg(feature = "rayon")]
+pub use interop::{PAR_THRESHOLD,par_into_strings,par_join_to_string};
+#[cfg(feature = "rust-decimal")]
+pub use interop::decimal_into_string_normalized;
+#[cfg(feature = "serde")]
+pub use interop::{LossyStr,serde_lossy};
+#[cfg(feature = "smartstring")]
+pub use interop::IntoSmartString;
+#[cfg(feature = "smol-str")]
+pub use interop::IntoSmolStr;
+#[cfg(feature = "tokio")]
+pub use interop::{AsyncReadLossyExt,read_to_string_lossy_async};
+#[cfg(feature = "tracing")]
+pub use interop::{DEFAULT_LOSSY_WARNING_LIMIT,set_lossy_warning_limit};
+#[cfg(feature = "url")]
+pub use interop::url_into_decoded_string;
+#[cfg(feature = "uuid")]
+pub use interop::{uuid_into_simple_string,uuid_into_urn_string};
+#[cfg(feature = "wasm")]
+pub use interop::{JS_VALUE_FALLBACK,js_value_into_string};
+#[cfg(all(windows, feature = "windows"))]
+pub use interop::{pcwstr_into_string,pwstr_into_string};
+
 /// Converts _something_ from the Rust standard library into
 /// a string.
 ///
@@ -21,8 +269,80 @@ use std::{
 /// a buffer full of U+FFFD characters.
 pub trait IntoString {
     fn into_string(self) -> String;
+
+    /// Appends the converted text to `buf`, the same text as `into_string`.
+    ///
+    /// The types which can be read without converting them first (`str`,
+    /// `CStr`, `OsStr`, ... behind a reference) copy straight into `buf`,
+    /// everything else converts and then appends.
+    fn push_into(self, buf: &mut String) where Self: Sized {
+        buf.push_str(&self.into_string());
+    }
+}
+
+/// The types which convert from behind a reference.
+///
+/// `&T` converts for every `T` here, and with the `deep-refs` feature so
+/// does `&&T` and any deeper reference, each level converting exactly like
+/// `&T` does. The owned `T` keeps its own `IntoString` impl, which is where
+/// the special cases that hand over the buffer live.
+///
+/// This is sealed, it is only implemented by this crate.
+pub trait IntoStringBase: sealed::Sealed {
+    fn ref_into_string(&self) -> String;
+
+    /// The `push_into` of `&Self`
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.ref_into_string());
+    }
+}
+impl<T: IntoStringBase + ?Sized> IntoString for &T {
+    fn into_string(self) -> String {
+        self.ref_into_string()
+    }
+    fn push_into(self, buf: &mut String) {
+        self.ref_push_into(buf);
+    }
+}
+#[cfg(feature = "deep-refs")]
+impl<T: IntoStringBase + ?Sized> sealed::Sealed for &T {}
+#[cfg(feature = "deep-refs")]
+impl<T: IntoStringBase + ?Sized> IntoStringBase for &T {
+    fn ref_into_string(&self) -> String {
+        (**self).ref_into_string()
+    }
+    fn ref_push_into(&self, buf: &mut String) {
+        (**self).ref_push_into(buf);
+    }
 }
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+// byte strings for the tests to compare conversions against
+// `String::from_utf8_lossy` with, valid and not
+#[cfg(test)]
+pub(crate) const TEST_BYTES: &[&[u8]] = &[
+    b"",
+    b"plain ascii",
+    "h\u{e9}llo \u{1F600}".as_bytes(),
+    b"\xFF",
+    b"a\xFFb\xFEc",
+    b"\xC3",
+    b"\xC3(",
+    b"\xE2\x82",
+    b"\xE2\x82\xAC\xE2",
+    b"\xF0\x9F\x98",
+    b"\xF0\x9F\x98\x80\x80",
+    b"\xE0\x80\x80",
+    b"\xED\xA0\x80",
+    b"\xF4\x90\x80\x80",
+    b"\xC0\x80",
+    b"\x80\x80\x80",
+    b"ok\xF8\x88\x80\x80\x80ok",
+];
+
 
 impl<'a> IntoString for Cow<'a,CStr> {
     // will attempt to gracefully transfer ownership
@@ -30,29 +350,18 @@ impl<'a> IntoString for Cow<'a,CStr> {
         match self {
             Cow::Owned(x) => <CString as IntoString>::into_string(x),
             Cow::Borrowed(x) => {
-                local_to_str(x.to_bytes())
+                local_to_str("Cow<CStr>", x.to_bytes())
             }
         }
     }
 }
-impl<'a> IntoString for &Cow<'a,CStr> {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&Cow<'a,CStr> {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&Cow<'a,CStr> {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+impl<'a> Sealed for Cow<'a,CStr> {}
+impl<'a> IntoStringBase for Cow<'a,CStr> {
+    fn ref_into_string(&self) -> String {
+        local_to_str("Cow<CStr>", self.to_bytes())
     }
-}
-impl<'a> IntoString for &&&&Cow<'a,CStr> {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
 
@@ -64,281 +373,373 @@ impl IntoString for CString {
         match CString::into_string(self) {
             Ok(x) => x,
             Err(e) => {
-                local_to_str(e.into_cstring().as_bytes())
+                local_to_str("CString", e.into_cstring().as_bytes())
             }
         }
     }
 }
-impl<'a> IntoString for &'a CString {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&'a CString {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&'a CString {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+impl Sealed for CString {}
+impl IntoStringBase for CString {
+    fn ref_into_string(&self) -> String {
+        local_to_str("CString", self.to_bytes())
     }
-}
-impl<'a> IntoString for &&&&'a CString {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&&&'a CString {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
 
-impl<'a> IntoString for &'a CStr {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+impl Sealed for CStr {}
+impl IntoStringBase for CStr {
+    fn ref_into_string(&self) -> String {
+        local_to_str("CStr", self.to_bytes())
     }
-}
-impl<'a> IntoString for &&'a CStr {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&'a CStr {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&&'a CStr {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
-    }
-}
-impl<'a> IntoString for &&&&&'a CStr {
-    fn into_string(self) -> String {
-        local_to_str(self.to_bytes())
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
 
 
 
-impl<'a> IntoString for Cow<'a,OsStr> {
+impl<'a> IntoString for Cow<'a,str> {
     /// Special case.
     ///
     /// This will inspect cow to see if the interior buffer is
-    /// owned and perform a similiar inspect to `OsString::into_string`.
+    /// owned and will avoid copying that case.
     fn into_string(self) -> String {
         match self {
-            Cow::Owned(x) => {
-                <OsString as IntoString>::into_string(x)
-            }
-            Cow::Borrowed(x) => x.into_string()
+            Cow::Owned(x) => x,
+            Cow::Borrowed(x) => x.to_string(),
         }
     }
 }
-impl<'a> IntoString for &Cow<'a,OsStr> {
-    fn into_string(self) -> String {
-        self.to_string_lossy().to_string()
-    }
-}
-impl<'a> IntoString for &&Cow<'a,OsStr> {
-    fn into_string(self) -> String {
-        self.to_string_lossy().to_string()
+impl<'a> Sealed for Cow<'a,str> {}
+impl<'a> IntoStringBase for Cow<'a,str> {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
     }
-}
-impl<'a> IntoString for &&&Cow<'a,OsStr> {
-    fn into_string(self) -> String {
-        self.to_string_lossy().to_string()
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
-impl<'a> IntoString for &&&&Cow<'a,OsStr> {
-    fn into_string(self) -> String {
-        self.to_string_lossy().to_string()
+
+impl Sealed for str {}
+impl IntoStringBase for str {
+    fn ref_into_string(&self) -> String {
+        self.to_string()
     }
-}
-impl<'a> IntoString for &&&&&Cow<'a,OsStr> {
-    fn into_string(self) -> String {
-        self.to_string_lossy().to_string()
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
 
-
-impl IntoString for OsString {
-    /// Special Case
-    ///
-    /// This call will first attempt
-    /// [`into_string`](https://doc.rust-lang.org/std/ffi/struct.OsString.html#method.into_string)
-    /// which will perserve ownership, falling back on the standard `to_string_lossy` method if
-    /// that fails.
-    ///
-    /// That is to say it will optimistically attempt to transfer ownership without cloning
-    /// the buffer.
+impl IntoString for String {
+    /// Special case, absolutely nothing is done
     fn into_string(self) -> String {
-        match OsString::into_string(self) {
-            Ok(x) => x,
-            Err(e) => {
-                e.to_string_lossy().into_string()
-            }
-        }
+        self
     }
 }
-impl<'a> IntoString for &'a OsString {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl Sealed for String {}
+impl IntoStringBase for String {
+    fn ref_into_string(&self) -> String {
+        self.clone()
     }
-}
-impl<'a> IntoString for &&'a OsString {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
     }
 }
-impl<'a> IntoString for &&&'a OsString {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
-    }
+
+/// A borrowed view of the text, without allocating when it is already valid.
+///
+/// Follows the same rules as the `IntoString` impl of the type. This is
+/// used by the output types which would rather copy from a `&str` than
+/// build an intermediate `String`.
+pub trait AsLossyStr {
+    fn as_lossy_str(&self) -> Cow<'_,str>;
 }
-impl<'a> IntoString for &&&&'a OsString {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl<T: AsLossyStr + ?Sized> AsLossyStr for &T {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        (**self).as_lossy_str()
     }
 }
-impl<'a> IntoString for &&&&&'a OsString {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for str {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        Cow::Borrowed(self)
     }
 }
-
-impl<'a> IntoString for &'a OsStr {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for String {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        Cow::Borrowed(self.as_str())
     }
 }
-impl<'a> IntoString for &&'a OsStr {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for CStr {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        match local_validate(self.to_bytes()) {
+            Some(x) => {
+                count_conversion();
+                Cow::Borrowed(x)
+            }
+            None => Cow::Owned(local_to_str("CStr", self.to_bytes())),
+        }
     }
 }
-impl<'a> IntoString for &&&'a OsStr {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for CString {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        self.as_c_str().as_lossy_str()
     }
 }
-impl<'a> IntoString for &&&&'a OsStr {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for Cow<'_,str> {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        Cow::Borrowed(self)
     }
 }
-impl<'a> IntoString for &&&&&'a OsStr {
-    fn into_string(self) -> String {
-        self.to_string_lossy().into_string()
+impl AsLossyStr for Cow<'_,CStr> {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        (**self).as_lossy_str()
     }
 }
 
-impl<'a> IntoString for Cow<'a,str> {
-    /// Special case.
-    ///
-    /// This will inspect cow to see if the interior buffer is
-    /// owned and will avoid copying that case.
-    fn into_string(self) -> String {
-        match self {
-            Cow::Owned(x) => x,
-            Cow::Borrowed(x) => x.to_string(),
+// `source` is the type being converted, see `lossy`
+fn local_to_str(source: &'static str, x: &[u8]) -> String {
+    count_conversion();
+    match local_validate(x) {
+        Some(x) => x.to_string(),
+        None => {
+            lossy(source, x, x.len());
+            let mut s = String::with_capacity(x.len());
+            s.extend((0..x.len()).map(|_| -> char { '\u{FFFD}' }));
+            s
         }
     }
 }
-impl<'a> IntoString for &Cow<'a,str> {
-    fn into_string(self) -> String {
-        self.to_string()
+
+// unlike `local_to_str` this keeps the buffer when it is valid utf8,
+// and only replaces the bad sequences (same as `String::from_utf8_lossy`)
+fn local_vec_to_str(source: &'static str, x: Vec<u8>) -> String {
+    if local_validate(&x).is_some() {
+        count_conversion();
+        // SAFETY: just validated
+        return unsafe { String::from_utf8_unchecked(x) };
     }
+    local_to_cow(source, &x).into_owned()
 }
-impl<'a> IntoString for &&Cow<'a,str> {
-    fn into_string(self) -> String {
-        self.to_string()
+
+// borrowed version of `local_vec_to_str`
+fn local_to_cow<'a>(source: &'static str, x: &'a [u8]) -> Cow<'a,str> {
+    count_conversion();
+    match local_validate(x) {
+        Some(x) => Cow::Borrowed(x),
+        None => {
+            lossy(source, x, invalid_runs(x));
+            String::from_utf8_lossy(x)
+        }
     }
 }
-impl<'a> IntoString for &&&Cow<'a,str> {
-    fn into_string(self) -> String {
-        self.to_string()
-    }
+
+// Every conversion which is about to replace invalid utf8 in `x` calls
+// this first, `replaced` is how many `U+FFFD` it is going to insert and
+// `source` the type being converted. This is where the `strict-debug`
+// panic, the `metrics` counters, the `tracing` events and the lossy hooks
+// all happen.
+#[allow(unused_variables)]
+fn lossy(source: &'static str, x: &[u8], replaced: usize) {
+    #[cfg(all(feature = "strict-debug", debug_assertions))]
+    if let Err(e) = core::str::from_utf8(x) {
+        panic!("lossy conversion of `{}`: invalid utf8 at byte {}", source, e.valid_up_to());
+    }
+    #[cfg(feature = "metrics")]
+    metrics::count_lossy(replaced);
+    #[cfg(feature = "tracing")]
+    interop::warn_lossy(source, x);
+    #[cfg(feature = "std")]
+    hook::report_lossy(source, x);
 }
-impl<'a> IntoString for &&&&Cow<'a,str> {
-    fn into_string(self) -> String {
-        self.to_string()
-    }
+
+// every conversion which checks its input calls this once
+#[inline(always)]
+fn count_conversion() {
+    #[cfg(feature = "metrics")]
+    metrics::count_conversion();
 }
-impl<'a> IntoString for &&&&&Cow<'a,str> {
-    fn into_string(self) -> String {
-        self.to_string()
-    }
+
+// the number of `U+FFFD` `String::from_utf8_lossy` puts in for `x`
+fn invalid_runs(x: &[u8]) -> usize {
+    x.utf8_chunks().filter(|x| !x.invalid().is_empty()).count()
 }
 
-impl<'a> IntoString for &'a str {
-    fn into_string(self) -> String {
-        self.to_string()
+// Every conversion of bytes checks them here first. With the `simd`
+// feature this is `simdutf8`, which only says whether the input is
+// valid, anything which needs to know where the error is (the lossy
+// replacement itself) uses std on the slow path.
+fn local_validate(x: &[u8]) -> Option<&str> {
+    #[cfg(feature = "simd")]
+    {
+        simdutf8::basic::from_utf8(x).ok()
     }
-}
-impl<'a> IntoString for &&'a str {
-    fn into_string(self) -> String {
-        self.to_string()
+    #[cfg(not(feature = "simd"))]
+    {
+        core::str::from_utf8(x).ok()
     }
 }
-impl<'a> IntoString for &&&'a str {
-    fn into_string(self) -> String {
-        self.to_string()
-    }
+
+// utf16 with unpaired surrogates replaced, one `U+FFFD` per surrogate
+fn local_wide_to_str(x: &[u16]) -> String {
+    char::decode_utf16(x.iter().copied())
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
 }
-impl<'a> IntoString for &&&&'a str {
-    fn into_string(self) -> String {
-        self.to_string()
-    }
+
+// utf32 with surrogates and values past `char::MAX` replaced
+#[allow(dead_code)]
+fn local_wide32_to_str(x: &[u32]) -> String {
+    x.iter()
+        .map(|c| char::from_u32(*c).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
 }
-impl<'a> IntoString for &&&&&'a str {
-    fn into_string(self) -> String {
-        self.to_string()
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn byte_helpers_match_from_utf8_lossy() {
+        for bytes in TEST_BYTES {
+            let expected = String::from_utf8_lossy(bytes);
+            assert_eq!(local_to_cow("[u8]", bytes), expected);
+            assert_eq!(local_vec_to_str("Vec<u8>", bytes.to_vec()), expected);
+        }
     }
-}
 
-impl IntoString for String {
-    /// Special case, absolutely nothing is done
-    fn into_string(self) -> String {
-        self
+    // Run with and without `simd`, the answer has to be std's either way.
+    // `simdutf8` only takes its vector path on longer input, so every case
+    // is also tried inside ascii padding at a few offsets.
+    #[test]
+    fn validate_matches_std() {
+        let mut corpus: Vec<Vec<u8>> = TEST_BYTES.iter().map(|x| x.to_vec()).collect();
+        for a in 0..=255u8 {
+            corpus.push(vec![a]);
+            for b in 0x80..=0xBFu8 {
+                corpus.push(vec![a, b]);
+                corpus.push(vec![a, b, 0x80]);
+            }
+        }
+        for case in corpus {
+            for offset in [0, 1, 31, 63, 64, 100] {
+                let mut padded = vec![b'x'; offset];
+                padded.extend_from_slice(&case);
+                padded.resize(padded.len() + 150, b'y');
+                for x in [&case[..], &padded[..]] {
+                    assert_eq!(local_validate(x), core::str::from_utf8(x).ok());
+                    assert_eq!(local_to_cow("[u8]", x), String::from_utf8_lossy(x));
+                }
+            }
+        }
     }
-}
-impl IntoString for &String {
-    fn into_string(self) -> String {
-        self.clone()
+
+    // Passes with and without `deep-refs`, which must not change anything
+    // for a single `&`: `cargo test --no-default-features --features std`
+    #[test]
+    fn one_level_of_reference() {
+        let text = String::from("text");
+        let c = CString::new("c\u{e9}").unwrap();
+        let bad = CString::new(b"a\xFF".to_vec()).unwrap();
+        assert_eq!((&*text).into_string(), "text");
+        assert_eq!((&text).into_string(), "text");
+        assert_eq!((&Cow::Borrowed("cow")).into_string(), "cow");
+        assert_eq!(c.as_c_str().into_string(), "c\u{e9}");
+        assert_eq!((&c).into_string(), "c\u{e9}");
+        assert_eq!((&bad).into_string(), "\u{FFFD}\u{FFFD}");
+        assert_eq!((&Cow::Borrowed(bad.as_c_str())).into_string(), "\u{FFFD}\u{FFFD}");
+        let mut buf = String::from("> ");
+        (&text).push_into(&mut buf);
+        bad.as_c_str().push_into(&mut buf);
+        assert_eq!(buf, "> text\u{FFFD}\u{FFFD}");
     }
-}
-impl IntoString for &&String {
-    fn into_string(self) -> String {
-        self.to_string()
+
+    #[cfg(feature = "deep-refs")]
+    #[test]
+    fn deeper_references_convert_like_one() {
+        let bad = CString::new(b"a\xFF".to_vec()).unwrap();
+        assert_eq!((&&"two").into_string(), "two");
+        assert_eq!((&&&bad).into_string(), (&bad).into_string());
+        let mut buf = String::new();
+        (&&&bad).push_into(&mut buf);
+        assert_eq!(buf, "\u{FFFD}\u{FFFD}");
     }
-}
-impl IntoString for &&&String {
-    fn into_string(self) -> String {
-        self.to_string()
+
+    #[cfg(feature = "deep-refs")]
+    #[test]
+    fn eight_levels_for_every_base_type() {
+        let c = CString::new("c").unwrap();
+        assert_eq!((&&&&&&&&"str").into_string(), "str");
+        assert_eq!((&&&&&&&&String::from("String")).into_string(), "String");
+        assert_eq!((&&&&&&&&c.as_c_str()).into_string(), "c");
+        assert_eq!((&&&&&&&&c).into_string(), "c");
+        assert_eq!((&&&&&&&&Cow::Borrowed("cow")).into_string(), "cow");
+        assert_eq!((&&&&&&&&Cow::Borrowed(c.as_c_str())).into_string(), "c");
+        assert_eq!((&&&&&&&&255u8).into_string_radix(16), "ff");
+        assert_eq!((&&&&&&&&-255i64).into_string_radix(36), "-73");
+        assert_eq!((&&&&&&&&ViaDisplay(1.5)).into_string(), "1.5");
+        #[cfg(feature = "std")]
+        {
+            use std::{ffi::{OsStr,OsString},io::ErrorKind};
+
+            assert_eq!((&&&&&&&&OsStr::new("os")).into_string(), "os");
+            assert_eq!((&&&&&&&&OsString::from("os")).into_string(), "os");
+            assert_eq!((&&&&&&&&Cow::Borrowed(OsStr::new("os"))).into_string(), "os");
+            assert_eq!((&&&&&&&&ErrorKind::NotFound).into_string(), "entity not found");
+            assert_eq!((&&&&&&&&std::env::VarError::NotUnicode("var".into())).into_string(), "var");
+        }
     }
-}
-impl IntoString for &&&&String {
-    fn into_string(self) -> String {
-        self.to_string()
+
+    // any `T` with a base impl, through ten levels of reference
+    #[cfg(feature = "deep-refs")]
+    fn ten_levels<T: IntoStringBase + ?Sized>(x: &T) -> String {
+        (&&&&&&&&&x).into_string()
     }
-}
-impl IntoString for &&&&&String {
-    fn into_string(self) -> String {
-        self.to_string()
+
+    #[cfg(feature = "deep-refs")]
+    #[test]
+    fn ten_levels_of_reference() {
+        let c = CString::new(b"a\xFF".to_vec()).unwrap();
+        assert_eq!(ten_levels("str"), "str");
+        assert_eq!(ten_levels(&String::from("String")), "String");
+        assert_eq!(ten_levels(c.as_c_str()), "\u{FFFD}\u{FFFD}");
+        assert_eq!(ten_levels(&Cow::Borrowed("cow")), "cow");
+        #[cfg(feature = "std")]
+        assert_eq!(ten_levels(std::ffi::OsStr::new("os")), "os");
     }
-}
 
+    #[test]
+    fn owned_values_keep_their_buffer() {
+        fn keeps<T: IntoString>(value: T, ptr: *const u8, expected: &str) {
+            let text = value.into_string();
+            assert_eq!(text, expected);
+            assert_eq!(text.as_ptr(), ptr);
+        }
+        let text = String::from("String");
+        let ptr = text.as_ptr();
+        keeps(text, ptr, "String");
+        let text = String::from("Cow<str>");
+        let ptr = text.as_ptr();
+        keeps(Cow::<str>::Owned(text), ptr, "Cow<str>");
+        let c = CString::new("CString").unwrap();
+        let ptr = c.as_ptr().cast();
+        keeps(c, ptr, "CString");
+        let c = CString::new("Cow<CStr>").unwrap();
+        let ptr = c.as_ptr().cast();
+        keeps(Cow::<CStr>::Owned(c), ptr, "Cow<CStr>");
+        #[cfg(feature = "std")]
+        {
+            use std::ffi::{OsStr,OsString};
 
-fn local_to_str(x: &[u8]) -> String {
-    match std::str::from_utf8(x) {
-        Ok(x) => x.to_string(),
-        Err(_) => {
-            let mut s = String::with_capacity(x.len());
-            s.extend((0..x.len()).map(|_| -> char { '\u{FFFD}' }));
-            s
+            let os = OsString::from("OsString");
+            let ptr = os.as_encoded_bytes().as_ptr();
+            keeps(os, ptr, "OsString");
+            let os = OsString::from("Cow<OsStr>");
+            let ptr = os.as_encoded_bytes().as_ptr();
+            keeps(Cow::<OsStr>::Owned(os), ptr, "Cow<OsStr>");
         }
     }
 }
@@ -0,0 +1,83 @@
+//! Lines of bytes which are already in memory.
+
+use alloc::{
+    borrow::Cow,
+    string::String,
+};
+
+use super::local_to_cow;
+
+/// Splits `bytes` into lines and converts each one on its own, for a whole
+/// file which was read (or mapped) in one go.
+///
+/// Lines end at `\n`, and a `\r` right before it is removed as well. The
+/// last line is returned whether or not it ends with a newline, an empty
+/// `bytes` has no lines. These are the same lines as
+/// [`BufReadExt::lossy_lines`](crate::io::BufReadExt::lossy_lines) gives.
+pub fn lossy_lines(bytes: &[u8]) -> impl Iterator<Item = String> + '_ {
+    lossy_lines_cow(bytes).map(Cow::into_owned)
+}
+
+/// Same as [`lossy_lines`], borrowing every line which is valid utf8
+pub fn lossy_lines_cow(bytes: &[u8]) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+    let mut rest = Some(bytes).filter(|x| !x.is_empty());
+    core::iter::from_fn(move || {
+        let bytes = rest?;
+        let line = match bytes.iter().position(|x| *x == b'\n') {
+            Some(at) => {
+                rest = Some(&bytes[at + 1..]).filter(|x| !x.is_empty());
+                let line = &bytes[..at];
+                line.strip_suffix(b"\r").unwrap_or(line)
+            }
+            None => {
+                rest = None;
+                bytes
+            }
+        };
+        Some(local_to_cow("[u8]", line))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn crlf_and_lf() {
+        let lines: Vec<String> = lossy_lines(b"one\r\ntwo\nthree\r\n").collect();
+        assert_eq!(lines, ["one", "two", "three"]);
+        // only the `\r` right before the `\n`
+        let lines: Vec<String> = lossy_lines(b"a\r\r\nb\rc\n").collect();
+        assert_eq!(lines, ["a\r", "b\rc"]);
+    }
+
+    #[test]
+    fn invalid_byte_in_one_line() {
+        let lines: Vec<Cow<'_, str>> = lossy_lines_cow(b"fine\nb\xFFd\nalso fine").collect();
+        assert_eq!(lines, ["fine", "b\u{FFFD}d", "also fine"]);
+        assert!(matches!(lines[0], Cow::Borrowed(_)));
+        assert!(matches!(lines[1], Cow::Owned(_)));
+        assert!(matches!(lines[2], Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn empty_and_unterminated() {
+        assert_eq!(lossy_lines(b"").count(), 0);
+        assert_eq!(lossy_lines(b"no newline").collect::<Vec<_>>(), ["no newline"]);
+        assert_eq!(lossy_lines(b"\n").collect::<Vec<_>>(), [""]);
+        assert_eq!(lossy_lines(b"\n\nx").collect::<Vec<_>>(), ["", "", "x"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn same_as_buf_read() {
+        use crate::io::BufReadExt;
+
+        for bytes in [&b"a\r\nb\nc"[..], b"a\n\n", b"\xFF\r\n\r\n", b"", b"\r"] {
+            let from_reader: Vec<String> = std::io::Cursor::new(bytes).lossy_lines().map(Result::unwrap).collect();
+            assert_eq!(lossy_lines(bytes).collect::<Vec<_>>(), from_reader, "{bytes:?}");
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Wrappers for passing a value on to APIs which want a string.
+
+use alloc::string::String;
+
+use super::IntoString;
+
+/// Makes any [`IntoString`] type an `Into<String>`, for APIs which take
+/// an `impl Into<String>`.
+///
+/// `set_name(Lossy(os_string))` converts `os_string` with its
+/// `IntoString` impl where `set_name` asks for it, instead of at every
+/// call site. std has no `From` for `OsString`, `CString` and the
+/// like, since it would have to pick between failing and losing data.
+#[repr(transparent)]
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Lossy<T>(pub T);
+
+impl<T: IntoString> From<Lossy<T>> for String {
+    fn from(value: Lossy<T>) -> String {
+        value.0.into_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        ffi::CString,
+        format,
+    };
+
+    use super::*;
+
+    fn set_name(name: impl Into<String>) -> String {
+        name.into()
+    }
+
+    #[test]
+    fn into_string_bound() {
+        let c = CString::new(b"c\xFF".to_vec()).unwrap();
+        assert_eq!(set_name(Lossy(c.as_c_str())), "\u{FFFD}\u{FFFD}");
+        assert_eq!(set_name(Lossy(c)), "\u{FFFD}\u{FFFD}");
+        assert_eq!(set_name(Lossy("str")), "str");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_strings() {
+        use std::ffi::{OsStr,OsString};
+
+        assert_eq!(set_name(Lossy(OsStr::new("borrowed"))), "borrowed");
+        let os = OsString::from("owned");
+        let ptr = os.as_encoded_bytes().as_ptr();
+        let text = set_name(Lossy(os));
+        assert_eq!(text, "owned");
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn transparent_wrapper() {
+        assert_eq!(size_of::<Lossy<CString>>(), size_of::<CString>());
+        let c = Lossy(CString::new("c").unwrap());
+        assert_eq!(c.clone(), c);
+        assert_eq!(format!("{:?}", c), "Lossy(\"c\")");
+    }
+}
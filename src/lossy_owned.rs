@@ -0,0 +1,108 @@
+//! A converted `String` which knows whether it was lossy.
+
+use std::{
+    ffi::OsStr,
+    fmt,
+    ops::Deref,
+};
+
+use super::{IntoString,hook};
+
+/// A converted `String` which remembers whether the conversion was lossy,
+/// in place of a `String` and a separate `bool`.
+///
+/// Borrows as a `&str` (and an `&OsStr`, to hand it back to std). Only
+/// conversions of bytes can tell they were lossy, UTF-16 and UTF-32 input
+/// (`U16String` and the like) always says it was not.
+#[derive(Clone,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct LossyOwned {
+    text: String,
+    was_lossy: bool,
+}
+
+impl LossyOwned {
+
+    /// Converts `value`, noting whether anything had to be replaced
+    pub fn new(value: impl IntoString) -> Self {
+        let (text, was_lossy) = hook::was_lossy(|| value.into_string());
+        Self { text, was_lossy }
+    }
+
+    /// Whether the conversion replaced anything with `U+FFFD`
+    pub fn was_lossy(&self) -> bool {
+        self.was_lossy
+    }
+
+    /// The converted text
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+impl Deref for LossyOwned {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+impl AsRef<str> for LossyOwned {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+impl AsRef<OsStr> for LossyOwned {
+    fn as_ref(&self) -> &OsStr {
+        self.text.as_ref()
+    }
+}
+impl fmt::Display for LossyOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    #[test]
+    fn valid_input() {
+        let text = LossyOwned::new(OsStr::new("name"));
+        assert!(!text.was_lossy());
+        assert_eq!(&*text, "name");
+        assert_eq!(text.len(), 4);
+        assert_eq!(AsRef::<str>::as_ref(&text), "name");
+        assert_eq!(AsRef::<OsStr>::as_ref(&text), OsStr::new("name"));
+        assert_eq!(text.to_string(), "name");
+        assert_eq!(text.into_string(), "name");
+    }
+
+    #[test]
+    fn lossy_input() {
+        let text = LossyOwned::new(CString::new(b"a\xFF".to_vec()).unwrap());
+        assert!(text.was_lossy());
+        assert_eq!(&*text, "\u{FFFD}\u{FFFD}");
+        assert_eq!(format!("[{}]", text), "[\u{FFFD}\u{FFFD}]");
+        assert_eq!(text.into_string(), "\u{FFFD}\u{FFFD}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lossy_os_string() {
+        use std::{ffi::OsString,os::unix::ffi::OsStringExt};
+
+        let text = LossyOwned::new(OsString::from_vec(b"a\xFFb".to_vec()));
+        assert!(text.was_lossy());
+        assert_eq!(AsRef::<OsStr>::as_ref(&text), OsStr::new("a\u{FFFD}b"));
+    }
+
+    // a valid conversion after a lossy one does not inherit the flag
+    #[test]
+    fn each_conversion_on_its_own() {
+        assert!(LossyOwned::new(c"\xFF").was_lossy());
+        assert!(!LossyOwned::new(c"ok").was_lossy());
+        assert!(!LossyOwned::new(String::from("owned")).was_lossy());
+    }
+}
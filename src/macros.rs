@@ -0,0 +1,92 @@
+// `&&T` up to `&&&&&&&&T` for a type which already implements
+// `IntoStringRadix` for `&T`. Each level only derefs once and calls the
+// level below, so every depth converts exactly like `&T` does.
+//
+// The depth is the number of tokens after the `;` in the last arm, one per
+// level above `&T`, so raising it is a matter of adding one more.
+macro_rules! deep_radix_refs {
+    (@level $ty:ty;) => {};
+    (@level $ty:ty; $_level:tt $($rest:tt)*) => {
+        #[cfg(feature = "deep-refs")]
+        impl $crate::IntoStringRadix for &&$ty {
+            fn into_string_radix(self, radix: u32) -> alloc::string::String {
+                $crate::IntoStringRadix::into_string_radix(*self, radix)
+            }
+        }
+        deep_radix_refs!(@level &$ty; $($rest)*);
+    };
+    ($ty:ty) => {
+        deep_radix_refs!(@level $ty; 2 3 4 5 6 7 8);
+    };
+}
+
+// `IntoStringRadix` and `IntoHexString` for primitive integers, `T`, `&T`
+// and with `deep-refs` the deeper references. Every type is split into a
+// sign and a `u128` magnitude, which `radix::write_radix` writes out.
+macro_rules! int_radix {
+    (unsigned $($ty:ty),*) => {
+        $(int_radix!(@impl $ty, x => (false, x as u128));)*
+    };
+    (signed $($ty:ty),*) => {
+        $(int_radix!(@impl $ty, x => (x < 0, x.unsigned_abs() as u128));)*
+    };
+    (@impl $ty:ty, $x:ident => $parts:expr) => {
+        impl $crate::IntoStringRadix for $ty {
+            fn into_string_radix(self, radix: u32) -> alloc::string::String {
+                $crate::radix::check_radix(radix);
+                let $x = self;
+                let (negative, magnitude) = $parts;
+                $crate::radix::write_radix(negative, magnitude, radix, "", 0)
+            }
+        }
+        impl $crate::IntoStringRadix for &$ty {
+            fn into_string_radix(self, radix: u32) -> alloc::string::String {
+                $crate::IntoStringRadix::into_string_radix(*self, radix)
+            }
+        }
+        deep_radix_refs!($ty);
+
+        impl $crate::IntoHexString for $ty {
+            fn into_hex_string(self) -> alloc::string::String {
+                $crate::IntoHexString::into_hex_string_with(self, false, false)
+            }
+            fn into_hex_string_with(self, prefix: bool, pad: bool) -> alloc::string::String {
+                let $x = self;
+                let (negative, magnitude) = $parts;
+                let prefix = if prefix { "0x" } else { "" };
+                let width = if pad { core::mem::size_of::<$ty>() * 2 } else { 0 };
+                $crate::radix::write_radix(negative, magnitude, 16, prefix, width)
+            }
+        }
+        impl $crate::IntoHexString for &$ty {
+            fn into_hex_string(self) -> alloc::string::String {
+                $crate::IntoHexString::into_hex_string(*self)
+            }
+            fn into_hex_string_with(self, prefix: bool, pad: bool) -> alloc::string::String {
+                $crate::IntoHexString::into_hex_string_with(*self, prefix, pad)
+            }
+        }
+    };
+}
+
+// `LossyPieces` for primitive integers, for the fixed capacity outputs.
+// The digits are written out on the stack first and go in with
+// `put_whole`, a number which does not fit is left out entirely rather
+// than cut part way through.
+#[cfg(any(feature = "arrayvec", feature = "heapless"))]
+macro_rules! int_pieces {
+    ($($ty:ty),*) => {
+        $(
+            impl $crate::fixed::LossyPieces for $ty {
+                fn write_pieces<B: $crate::fixed::FixedBuf>(
+                    self,
+                    w: &mut $crate::fixed::Writer<'_, B>,
+                ) -> Result<(), $crate::fixed::Full> {
+                    let mut digits = $crate::fixed::Digits::new();
+                    let _ = core::fmt::Write::write_fmt(&mut digits, format_args!("{}", self));
+                    w.put_whole(digits.as_str())
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,56 @@
+//! Counters of the conversions done by the whole process.
+
+use core::sync::atomic::{AtomicU64,Ordering};
+
+static CONVERSIONS: AtomicU64 = AtomicU64::new(0);
+static LOSSY: AtomicU64 = AtomicU64::new(0);
+static REPLACEMENTS: AtomicU64 = AtomicU64::new(0);
+
+/// The counters at the time of [`conversion_stats`].
+///
+/// Only the conversions which have to check their input count, which is
+/// everything that starts out as bytes (`OsStr`, `CStr`, `BString`, a
+/// [`LossyDecoder`](crate::LossyDecoder) ...). A `str` can not be lossy and
+/// is not counted. A valid input costs one relaxed increment, a lossy one
+/// two more.
+///
+/// A `LossyDecoder` is one conversion, but counts as lossy once for every
+/// piece it had to replace something in (and once more for an input which
+/// ends part way through a sequence).
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct ConversionStats {
+    /// Conversions which checked their input, lossy or not
+    pub conversions: u64,
+    /// Conversions which replaced at least one bad sequence
+    pub lossy: u64,
+    /// `U+FFFD`s inserted by the lossy conversions
+    pub replacements: u64,
+}
+
+/// Reads the counters.
+///
+/// Each counter is read on its own, a conversion running on another thread
+/// at the same time may show up in one and not yet in the others.
+pub fn conversion_stats() -> ConversionStats {
+    ConversionStats {
+        conversions: CONVERSIONS.load(Ordering::Relaxed),
+        lossy: LOSSY.load(Ordering::Relaxed),
+        replacements: REPLACEMENTS.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets every counter back to zero
+pub fn reset_conversion_stats() {
+    CONVERSIONS.store(0, Ordering::Relaxed);
+    LOSSY.store(0, Ordering::Relaxed);
+    REPLACEMENTS.store(0, Ordering::Relaxed);
+}
+
+pub(crate) fn count_conversion() {
+    CONVERSIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_lossy(replaced: usize) {
+    LOSSY.fetch_add(1, Ordering::Relaxed);
+    REPLACEMENTS.fetch_add(replaced as u64, Ordering::Relaxed);
+}
@@ -0,0 +1,141 @@
+//! Impls for the types which only exist with `std`, `OsStr` and `Path`.
+
+use std::{
+    borrow::Cow,
+    ffi::{OsStr,OsString},
+    path::{Path,PathBuf},
+};
+
+use super::{AsLossyStr,IntoString,IntoStringBase,count_conversion,invalid_runs,lossy,sealed::Sealed};
+
+impl<'a> IntoString for Cow<'a,OsStr> {
+    /// Special case.
+    ///
+    /// This will inspect cow to see if the interior buffer is
+    /// owned and perform a similiar inspect to `OsString::into_string`.
+    fn into_string(self) -> String {
+        match self {
+            Cow::Owned(x) => {
+                os_string_to_str("Cow<OsStr>", x)
+            }
+            Cow::Borrowed(x) => x.into_string()
+        }
+    }
+}
+impl<'a> Sealed for Cow<'a,OsStr> {}
+impl<'a> IntoStringBase for Cow<'a,OsStr> {
+    fn ref_into_string(&self) -> String {
+        os_str_to_str("Cow<OsStr>", self)
+    }
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
+    }
+}
+
+
+impl IntoString for OsString {
+    /// Special Case
+    ///
+    /// This call will first attempt
+    /// [`into_string`](https://doc.rust-lang.org/std/ffi/struct.OsString.html#method.into_string)
+    /// which will perserve ownership, falling back on the standard `to_string_lossy` method if
+    /// that fails.
+    ///
+    /// That is to say it will optimistically attempt to transfer ownership without cloning
+    /// the buffer.
+    fn into_string(self) -> String {
+        os_string_to_str("OsString", self)
+    }
+}
+impl Sealed for OsString {}
+impl IntoStringBase for OsString {
+    fn ref_into_string(&self) -> String {
+        os_str_to_str("OsString", self)
+    }
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
+    }
+}
+
+impl Sealed for OsStr {}
+impl IntoStringBase for OsStr {
+    fn ref_into_string(&self) -> String {
+        os_str_to_str("OsStr", self)
+    }
+    fn ref_push_into(&self, buf: &mut String) {
+        buf.push_str(&self.as_lossy_str());
+    }
+}
+
+// `source` is the type being converted, see `lossy`
+fn os_string_to_str(source: &'static str, x: OsString) -> String {
+    count_conversion();
+    match x.into_string() {
+        Ok(x) => x,
+        Err(e) => {
+            lossy(source, e.as_encoded_bytes(), os_replaced(e.as_encoded_bytes()));
+            e.to_string_lossy().into_string()
+        }
+    }
+}
+
+// the `PathBuf`s returned by std, converted like an `OsString`
+pub(crate) fn path_buf_to_str(x: PathBuf) -> String {
+    os_string_to_str("PathBuf", x.into_os_string())
+}
+
+// same as `path_buf_to_str`, for borrowed input
+pub(crate) fn path_to_str(x: &Path) -> String {
+    os_str_to_str("Path", x.as_os_str())
+}
+
+// same as `os_string_to_str`, for borrowed input
+fn os_str_to_str(source: &'static str, x: &OsStr) -> String {
+    os_str_to_cow(source, x).into_owned()
+}
+
+// same as `os_str_to_str`, without copying valid text
+fn os_str_to_cow<'a>(source: &'static str, x: &'a OsStr) -> Cow<'a,str> {
+    count_conversion();
+    let text = x.to_string_lossy();
+    if let Cow::Owned(_) = text {
+        lossy(source, x.as_encoded_bytes(), os_replaced(x.as_encoded_bytes()));
+    }
+    text
+}
+
+impl AsLossyStr for OsStr {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        os_str_to_cow("OsStr", self)
+    }
+}
+impl AsLossyStr for OsString {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        os_str_to_cow("OsString", self)
+    }
+}
+impl AsLossyStr for Path {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        os_str_to_cow("Path", self.as_os_str())
+    }
+}
+impl AsLossyStr for PathBuf {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        os_str_to_cow("PathBuf", self.as_os_str())
+    }
+}
+impl AsLossyStr for Cow<'_,OsStr> {
+    fn as_lossy_str(&self) -> Cow<'_,str> {
+        os_str_to_cow("Cow<OsStr>", self)
+    }
+}
+
+// the number of `U+FFFD` in the lossy text of the raw bytes `x`
+fn os_replaced(x: &[u8]) -> usize {
+    // the only bad sequences in WTF-8 are unpaired surrogates, which are
+    // three bytes, each a bad sequence of its own as utf8
+    #[cfg(windows)]
+    return invalid_runs(x) / 3;
+    #[cfg(not(windows))]
+    invalid_runs(x)
+}
@@ -0,0 +1,298 @@
+//! Helpers for [`std::path`](https://doc.rust-lang.org/std/path/index.html) types.
+
+use std::{
+    env,
+    io,
+    path::{self,Component,MAIN_SEPARATOR,Path,PathBuf},
+};
+
+use super::{IntoString,convert::strip_verbatim,os::{path_buf_to_str,path_to_str}};
+
+/// Extension trait for [`Path`](https://doc.rust-lang.org/std/path/struct.Path.html)
+///
+/// These follow the std methods of the same name, so a path ending in `..`
+/// has no file name and a hidden file like `.bashrc` has no extension.
+pub trait PathExt {
+
+    /// See [`Path::file_name`](https://doc.rust-lang.org/std/path/struct.Path.html#method.file_name)
+    fn file_name_string(&self) -> Option<String>;
+
+    /// See [`Path::extension`](https://doc.rust-lang.org/std/path/struct.Path.html#method.extension)
+    fn extension_string(&self) -> Option<String>;
+
+    /// See [`Path::file_stem`](https://doc.rust-lang.org/std/path/struct.Path.html#method.file_stem)
+    fn file_stem_string(&self) -> Option<String>;
+
+    /// The whole path, same as `into_string`
+    fn to_lossy_string(&self) -> String;
+}
+
+impl PathExt for Path {
+    fn file_name_string(&self) -> Option<String> {
+        self.file_name().map(IntoString::into_string)
+    }
+    fn extension_string(&self) -> Option<String> {
+        self.extension().map(IntoString::into_string)
+    }
+    fn file_stem_string(&self) -> Option<String> {
+        self.file_stem().map(IntoString::into_string)
+    }
+    fn to_lossy_string(&self) -> String {
+        path_to_str(self)
+    }
+}
+
+/// Makes `path` absolute without touching the filesystem, as a `String`.
+///
+/// See [`std::path::absolute`](https://doc.rust-lang.org/std/path/fn.absolute.html),
+/// `..` and symlinks are left as they are (on Unix) and `path` does not
+/// have to exist. The buffer of the `PathBuf` is kept when it is valid utf8.
+pub fn absolute_lossy<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    path::absolute(path).map(path_buf_to_str)
+}
+
+/// Removes the Windows verbatim prefix from a converted path, which
+/// [`canonicalize_lossy`](crate::fs::canonicalize_lossy) always adds.
+///
+/// * `\\?\C:\dir` becomes `C:\dir`
+/// * `\\?\UNC\server\share` becomes `\\server\share`
+///
+/// Anything else, including verbatim paths with no drive letter such as
+/// `\\?\Volume{...}\` and device paths (`\\.\COM1`), is returned as it
+/// is. This only looks at the text, so it does the same on every platform.
+/// [`Converter::strip_verbatim`](crate::Converter::strip_verbatim) does the
+/// same as part of a conversion.
+pub fn strip_verbatim_prefix(mut path: String) -> String {
+    strip_verbatim(&mut path);
+    path
+}
+
+/// Converts `path` for showing to a user, with the home directory written
+/// as `~` (`~/projects/foo`).
+///
+/// The home directory is `HOME`, or `USERPROFILE` on Windows. When that is
+/// not set (or not absolute) or `path` is not under it, this is the normal
+/// lossy conversion, see [`path_display_tilde_in`].
+pub fn path_display_tilde(path: &Path) -> String {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    match env::var_os(var) {
+        Some(home) => path_display_tilde_in(path, Path::new(&home)),
+        None => path_to_str(path),
+    }
+}
+
+/// Same as [`path_display_tilde`] with `home` as the home directory.
+///
+/// Only whole components are matched, with `/home/user` as `home`
+/// `/home/user/x` is `~/x` while `/home/user2/x` stays as it is. `home`
+/// itself is `~`. Nothing is resolved, a path which only reaches `home`
+/// through `..` or a symlink is not shortened.
+pub fn path_display_tilde_in(path: &Path, home: &Path) -> String {
+    if !home.is_absolute() {
+        return path_to_str(path);
+    }
+    match path.strip_prefix(home) {
+        Ok(rest) if rest.as_os_str().is_empty() => String::from("~"),
+        Ok(rest) => {
+            let rest = path_to_str(rest);
+            let mut out = String::with_capacity(rest.len() + 2);
+            out.push('~');
+            out.push(MAIN_SEPARATOR);
+            out.push_str(&rest);
+            out
+        }
+        Err(_) => path_to_str(path),
+    }
+}
+
+/// Converts `path` relative to `base`, for showing paths under a project
+/// root, `/src/app/main.rs` relative to `/src/lib` is `../app/main.rs`.
+///
+/// This only looks at the components, nothing is read from the
+/// filesystem, so a symlink in `base` is not followed and `path` does not
+/// have to exist. `path` equal to `base` is `.`. When there is no relative
+/// form (one is absolute and the other is not, they are on different
+/// Windows drives, or `base` goes up with `..` where `path` does not) the
+/// whole `path` is converted instead.
+pub fn path_display_relative(path: &Path, base: &Path) -> String {
+    match relative_path(path, base) {
+        Some(relative) => path_buf_to_str(relative),
+        None => path_to_str(path),
+    }
+}
+
+// the same rules as the `pathdiff` crate
+fn relative_path(path: &Path, base: &Path) -> Option<PathBuf> {
+    let prefix = |x: &Path| match x.components().next() {
+        Some(Component::Prefix(prefix)) => Some(prefix.as_os_str().to_owned()),
+        _ => None,
+    };
+    if path.is_absolute() != base.is_absolute() || prefix(path) != prefix(base) {
+        return None;
+    }
+    let mut path = path.components();
+    let mut base = base.components();
+    let mut out: Vec<Component> = Vec::new();
+    loop {
+        match (path.next(), base.next()) {
+            (None, None) => break,
+            (Some(x), None) => {
+                out.push(x);
+                out.extend(path.by_ref());
+                break;
+            }
+            (None, Some(_)) => {
+                out.push(Component::ParentDir);
+                out.extend(base.by_ref().map(|_| Component::ParentDir));
+                break;
+            }
+            (Some(x), Some(y)) if out.is_empty() && x == y => {}
+            (Some(x), Some(Component::CurDir)) => out.push(x),
+            (Some(_), Some(Component::ParentDir)) => return None,
+            (Some(x), Some(_)) => {
+                out.push(Component::ParentDir);
+                out.extend(base.by_ref().map(|_| Component::ParentDir));
+                out.push(x);
+                out.extend(path.by_ref());
+                break;
+            }
+        }
+    }
+    if out.is_empty() {
+        return Some(PathBuf::from("."));
+    }
+    Some(out.iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_ext_plain() {
+        let path = Path::new("dir/archive.tar.gz");
+        assert_eq!(path.file_name_string().as_deref(), Some("archive.tar.gz"));
+        assert_eq!(path.extension_string().as_deref(), Some("gz"));
+        assert_eq!(path.file_stem_string().as_deref(), Some("archive.tar"));
+        assert_eq!(path.to_lossy_string(), "dir/archive.tar.gz");
+    }
+
+    #[test]
+    fn path_ext_edge_cases() {
+        let up = Path::new("dir/..");
+        assert_eq!(up.file_name_string(), None);
+        assert_eq!(up.extension_string(), None);
+        assert_eq!(up.file_stem_string(), None);
+        let hidden = Path::new(".bashrc");
+        assert_eq!(hidden.file_name_string().as_deref(), Some(".bashrc"));
+        assert_eq!(hidden.extension_string(), None);
+        assert_eq!(hidden.file_stem_string().as_deref(), Some(".bashrc"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_ext_invalid_bytes() {
+        use std::{ffi::OsStr,os::unix::ffi::OsStrExt};
+
+        let path = Path::new(OsStr::from_bytes(b"d\xFF/n\xFEme.t\xFD"));
+        assert_eq!(path.file_name_string().as_deref(), Some("n\u{FFFD}me.t\u{FFFD}"));
+        assert_eq!(path.extension_string().as_deref(), Some("t\u{FFFD}"));
+        assert_eq!(path.file_stem_string().as_deref(), Some("n\u{FFFD}me"));
+        assert_eq!(path.to_lossy_string(), "d\u{FFFD}/n\u{FFFD}me.t\u{FFFD}");
+    }
+
+    #[test]
+    fn absolute_without_the_filesystem() {
+        let cwd = std::env::current_dir().unwrap();
+        let expected = cwd.join("not").join("there");
+        assert_eq!(absolute_lossy("not/there").unwrap(), expected.to_str().unwrap());
+        assert!(Path::new(&absolute_lossy(".").unwrap()).is_absolute());
+        assert!(absolute_lossy("").is_err());
+        #[cfg(unix)]
+        assert_eq!(absolute_lossy("/a/../b").unwrap(), "/a/../b");
+    }
+
+    #[test]
+    fn verbatim_prefixes() {
+        assert_eq!(strip_verbatim_prefix(r"\\?\C:\dir\file".to_string()), r"C:\dir\file");
+        assert_eq!(strip_verbatim_prefix(r"\\?\UNC\server\share".to_string()), r"\\server\share");
+        // left alone
+        for path in [r"\\?\Volume{1234}\", r"\\.\COM1", r"C:\dir", "/usr/bin", r"\\?\UNC\", r"\\?\C"] {
+            assert_eq!(strip_verbatim_prefix(path.to_string()), path);
+        }
+        assert_eq!(crate::Converter::new().strip_verbatim(true).convert(r"\\?\D:\"), r"D:\");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tilde_under_home() {
+        let home = Path::new("/home/user");
+        assert_eq!(path_display_tilde_in(Path::new("/home/user/projects/foo"), home), "~/projects/foo");
+        assert_eq!(path_display_tilde_in(Path::new("/home/user/"), home), "~");
+        assert_eq!(path_display_tilde_in(home, home), "~");
+        // a trailing separator on home makes no difference
+        assert_eq!(path_display_tilde_in(Path::new("/home/user/x"), Path::new("/home/user/")), "~/x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tilde_outside_home() {
+        let home = Path::new("/home/user");
+        for path in ["/home/user2/x", "/home/use", "/home", "/etc/passwd", "home/user/x"] {
+            assert_eq!(path_display_tilde_in(Path::new(path), home), path);
+        }
+        // nothing is resolved
+        assert_eq!(path_display_tilde_in(Path::new("/home/user/../other"), home), "~/../other");
+        // a relative home is never matched
+        assert_eq!(path_display_tilde_in(Path::new("home/x"), Path::new("home")), "home/x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tilde_invalid_bytes() {
+        use std::{ffi::OsStr,os::unix::ffi::OsStrExt};
+
+        let home = Path::new(OsStr::from_bytes(b"/home/\xFF"));
+        let path = Path::new(OsStr::from_bytes(b"/home/\xFF/f\xFEle"));
+        assert_eq!(path_display_tilde_in(path, home), "~/f\u{FFFD}le");
+        assert_eq!(path_display_tilde_in(Path::new(OsStr::from_bytes(b"/tmp/\xFF")), home), "/tmp/\u{FFFD}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_child_sibling_and_same() {
+        let base = Path::new("/src/lib");
+        assert_eq!(path_display_relative(Path::new("/src/lib/a/b.rs"), base), "a/b.rs");
+        assert_eq!(path_display_relative(Path::new("/src/app/main.rs"), base), "../app/main.rs");
+        assert_eq!(path_display_relative(Path::new("/src"), base), "..");
+        assert_eq!(path_display_relative(Path::new("/other/x"), base), "../../other/x");
+        assert_eq!(path_display_relative(base, base), ".");
+        assert_eq!(path_display_relative(Path::new("/src/lib/"), base), ".");
+        // relative to relative
+        assert_eq!(path_display_relative(Path::new("a/b/c"), Path::new("a/d")), "../b/c");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_falls_back_to_the_whole_path() {
+        assert_eq!(path_display_relative(Path::new("/abs/x"), Path::new("rel")), "/abs/x");
+        assert_eq!(path_display_relative(Path::new("rel/x"), Path::new("/abs")), "rel/x");
+        assert_eq!(path_display_relative(Path::new("a/x"), Path::new("../b")), "a/x");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn relative_on_windows_drives() {
+        assert_eq!(path_display_relative(Path::new(r"C:\src\app"), Path::new(r"C:\src\lib")), r"..\app");
+        assert_eq!(path_display_relative(Path::new(r"D:\data\x"), Path::new(r"C:\src")), r"D:\data\x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_invalid_bytes() {
+        use std::{ffi::OsStr,os::unix::ffi::OsStrExt};
+
+        let path = Path::new(OsStr::from_bytes(b"/base/d\xFF/f"));
+        assert_eq!(path_display_relative(path, Path::new("/base/x")), "../d\u{FFFD}/f");
+    }
+}
@@ -0,0 +1,116 @@
+//! Lossy percent-decoding (`%XX` escapes, RFC 3986).
+//!
+//! Malformed escapes (`%G1`, a trailing `%`) are kept as literal text
+//! rather than being an error. `+` is _not_ decoded as a space, that is
+//! an `application/x-www-form-urlencoded` rule and not part of
+//! percent-encoding, so it is left as `+`.
+
+use alloc::{
+    borrow::Cow,
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::ffi::OsStr;
+
+use super::{local_to_cow,local_vec_to_str};
+
+/// Percent-decodes `input`, decoded bytes which are not valid utf8
+/// are replaced with `U+FFFD`.
+pub fn percent_decode_into_string(input: &str) -> String {
+    percent_decode_bytes_into_string(input.as_bytes())
+}
+
+/// Percent-decodes `input`, the result (escapes and all) is converted
+/// with the lossy rules.
+pub fn percent_decode_bytes_into_string(input: &[u8]) -> String {
+    match percent_decode(input) {
+        Cow::Borrowed(x) => local_to_cow("[u8]", x).into_owned(),
+        Cow::Owned(x) => local_vec_to_str("[u8]", x),
+    }
+}
+
+/// Percent-decodes `input`, for query strings and the like which were
+/// captured from the environment.
+///
+/// The raw representation is decoded (bytes on Unix, WTF-8 on Windows),
+/// so an unpaired surrogate on Windows ends up as `U+FFFD`.
+#[cfg(feature = "std")]
+pub fn percent_decode_os_str_into_string(input: &OsStr) -> String {
+    percent_decode_bytes_into_string(input.as_encoded_bytes())
+}
+
+// input without a `%` is returned as is
+fn percent_decode(input: &[u8]) -> Cow<'_,[u8]> {
+    if !input.contains(&b'%') {
+        return Cow::Borrowed(input);
+    }
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'%' {
+            let high = input.get(i + 1).and_then(|x| hex(*x));
+            let low = input.get(i + 2).and_then(|x| hex(*x));
+            if let (Some(high), Some(low)) = (high, low) {
+                out.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+    Cow::Owned(out)
+}
+
+fn hex(x: u8) -> Option<u8> {
+    match x {
+        b'0'..=b'9' => Some(x - b'0'),
+        b'a'..=b'f' => Some(x - b'a' + 10),
+        b'A'..=b'F' => Some(x - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_escapes() {
+        assert_eq!(percent_decode_into_string("caf%C3%A9%20au%20lait"), "café au lait");
+        assert_eq!(percent_decode_into_string("%e2%82%ac"), "€");
+        assert_eq!(percent_decode_into_string("no escapes"), "no escapes");
+    }
+
+    #[test]
+    fn malformed_escapes_are_kept() {
+        assert_eq!(percent_decode_into_string("%G1%"), "%G1%");
+        assert_eq!(percent_decode_into_string("100%"), "100%");
+        assert_eq!(percent_decode_into_string("%4"), "%4");
+        assert_eq!(percent_decode_into_string("%%41"), "%A");
+    }
+
+    #[test]
+    fn invalid_decoded_bytes() {
+        assert_eq!(percent_decode_into_string("a%FFb"), "a\u{FFFD}b");
+        assert_eq!(percent_decode_into_string("%C3"), "\u{FFFD}");
+        assert_eq!(percent_decode_bytes_into_string(b"\xFF%41"), "\u{FFFD}A");
+    }
+
+    #[test]
+    fn plus_is_not_a_space() {
+        assert_eq!(percent_decode_into_string("a+b%2Bc"), "a+b+c");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn os_str() {
+        assert_eq!(percent_decode_os_str_into_string(OsStr::new("q=%C3%A9+x")), "q=é+x");
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            assert_eq!(percent_decode_os_str_into_string(OsStr::from_bytes(b"\xFF%20")), "\u{FFFD} ");
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! `ExitStatus` of a child process, in the same form on every platform.
+//!
+//! * `exit code 0` when the process exited, with the code it exited with
+//! * `terminated by signal 9 (SIGKILL)` when it was killed by a signal on
+//!   Unix, the name is left off for signals without a portable number, and
+//!   `, core dumped` is added when it dumped core
+//!
+//! Anything else (a stopped or continued process) falls back to the
+//! `Display` of the `ExitStatus`.
+//!
+//! There is no impl for `ExitCode`, it only goes one way (into the process
+//! exit) and std has no way to read the code back out of it.
+//!
+//! [`CommandStringExt`] runs a `Command` and converts what it printed.
+
+use std::{
+    io,
+    process::{Command,ExitStatus},
+};
+
+use super::{IntoString,IntoStringBase,local_vec_to_str,sealed::Sealed};
+
+impl IntoString for ExitStatus {
+    fn into_string(self) -> String {
+        exit_status_string(&self)
+    }
+}
+impl Sealed for ExitStatus {}
+impl IntoStringBase for ExitStatus {
+    fn ref_into_string(&self) -> String {
+        exit_status_string(self)
+    }
+}
+
+fn exit_status_string(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exit code {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            let mut out = format!("terminated by signal {}", signal);
+            if let Some(name) = signal_name(signal) {
+                out.push_str(&format!(" ({})", name));
+            }
+            if status.core_dumped() {
+                out.push_str(", core dumped");
+            }
+            return out;
+        }
+    }
+    status.to_string()
+}
+
+// only the signals which have the same number on every Unix
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => return None,
+    })
+}
+
+/// What [`CommandStringExt::output_strings`] collected from a child process
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct CommandStrings {
+    /// How the process ended
+    pub status: ExitStatus,
+    /// Everything it wrote to stdout
+    pub stdout: String,
+    /// Everything it wrote to stderr
+    pub stderr: String,
+}
+
+/// Extension trait for [`Command`](https://doc.rust-lang.org/std/process/struct.Command.html)
+///
+/// Both run the command to the end the same as `Command::output`, an
+/// `Err` is only for a command which could not be run at all. The output
+/// is converted lossily, keeping the buffers when it is valid utf8.
+pub trait CommandStringExt {
+
+    /// Runs the command, returning how it ended and its stdout and stderr.
+    ///
+    /// Exiting with a non-zero status is not an error, check `status`.
+    fn output_strings(&mut self) -> io::Result<CommandStrings>;
+
+    /// Runs the command and returns its stdout without the one trailing
+    /// newline (`\n` or `\r\n`), like `$(...)` in a shell.
+    ///
+    /// The exit status is not looked at, use
+    /// [`output_strings`](CommandStringExt::output_strings) to check it.
+    fn stdout_string(&mut self) -> io::Result<String>;
+}
+
+impl CommandStringExt for Command {
+    fn output_strings(&mut self) -> io::Result<CommandStrings> {
+        let output = self.output()?;
+        Ok(CommandStrings {
+            status: output.status,
+            stdout: local_vec_to_str("Command", output.stdout),
+            stderr: local_vec_to_str("Command", output.stderr),
+        })
+    }
+    fn stdout_string(&mut self) -> io::Result<String> {
+        let mut stdout = self.output_strings()?.stdout;
+        if stdout.ends_with('\n') {
+            stdout.pop();
+            if stdout.ends_with('\r') {
+                stdout.pop();
+            }
+        }
+        Ok(stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn exit_codes_of_real_commands() {
+        let status = Command::new("true").status().unwrap();
+        assert_eq!(status.into_string(), "exit code 0");
+        let status = Command::new("sh").args(["-c", "exit 101"]).status().unwrap();
+        assert_eq!((&status).into_string(), "exit code 101");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signals_from_raw() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // a wait status, the code is in the second byte and the signal in
+        // the low 7 bits, with 0x80 set when it dumped core
+        assert_eq!(ExitStatus::from_raw(101 << 8).into_string(), "exit code 101");
+        assert_eq!(ExitStatus::from_raw(9).into_string(), "terminated by signal 9 (SIGKILL)");
+        assert_eq!(ExitStatus::from_raw(11 | 0x80).into_string(), "terminated by signal 11 (SIGSEGV), core dumped");
+        // SIGUSR1 is 10 on linux and 30 on macos, so has no name
+        assert_eq!(ExitStatus::from_raw(10).into_string(), "terminated by signal 10");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_output() {
+        let out = Command::new("sh").args(["-c", "printf 'a\\377b\\n'; printf err >&2; exit 3"]).output_strings().unwrap();
+        assert_eq!(out.stdout, "a\u{FFFD}b\n");
+        assert_eq!(out.stderr, "err");
+        assert_eq!(out.status.into_string(), "exit code 3");
+        assert_eq!(Command::new("sh").args(["-c", "printf 'line\\r\\n'"]).stdout_string().unwrap(), "line");
+        assert_eq!(Command::new("sh").args(["-c", "printf 'two\\n\\n'"]).stdout_string().unwrap(), "two\n");
+    }
+
+    #[test]
+    fn command_which_can_not_run() {
+        assert!(Command::new("this command does not exist").output_strings().is_err());
+    }
+
+    // `echo` through the shell of the platform
+    fn echo(text: &str) -> Command {
+        let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+        let mut command = Command::new(shell);
+        command.args([flag, &format!("echo {text}")]);
+        command
+    }
+
+    #[test]
+    fn stdout_string_trims_one_newline() {
+        assert_eq!(echo("hello").stdout_string().unwrap(), "hello");
+        let out = echo("hello").output_strings().unwrap();
+        assert!(out.status.success());
+        assert!(out.stdout == "hello\n" || out.stdout == "hello\r\n", "{:?}", out.stdout);
+        assert_eq!(out.stderr, "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdout_string_edges() {
+        let sh = |script: &str| Command::new("sh").args(["-c", script]).stdout_string().unwrap();
+        assert_eq!(sh("printf ''"), "");
+        assert_eq!(sh("printf '\\n'"), "");
+        assert_eq!(sh("printf 'no newline'"), "no newline");
+        assert_eq!(sh("printf 'lone\\r'"), "lone\r");
+        // the status is not looked at
+        assert_eq!(sh("echo failed; exit 1"), "failed");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_zero_exit_is_not_an_error() {
+        let out = Command::new("sh").args(["-c", "echo oops >&2; exit 42"]).output_strings().unwrap();
+        assert!(!out.status.success());
+        assert_eq!(out.status.code(), Some(42));
+        assert_eq!(out.stdout, "");
+        assert_eq!(out.stderr, "oops\n");
+    }
+}
@@ -0,0 +1,142 @@
+//! Numbers written in a radix other than 10.
+
+use alloc::string::String;
+
+/// Converts a number into a `String` in any radix from 2 to 36.
+///
+/// Digits past 9 are the lowercase letters `a` to `z`. A negative number
+/// is a `-` followed by its magnitude, `-255i16` in radix 16 is `-ff`.
+/// `format!("{:x}", -255i16)` gives the two's complement (`ff01`) instead,
+/// which only means anything when the width of the type is known and has
+/// no counterpart for a radix without a format.
+///
+/// # Panics
+///
+/// When `radix` is not in `2..=36`.
+pub trait IntoStringRadix {
+    fn into_string_radix(self, radix: u32) -> String;
+}
+
+/// Converts an integer into lowercase hexadecimal, the same sign rules as
+/// [`IntoStringRadix`].
+pub trait IntoHexString {
+
+    /// Same as `into_string_radix(16)`, `255u16` is `ff`
+    fn into_hex_string(self) -> String;
+
+    /// With `0x` in front when `prefix`, and padded with zeros to the width
+    /// of the type (two digits a byte) when `pad`. `255u16` with both is
+    /// `0x00ff` and `-1i8` is `-0x01`, the sign goes first.
+    fn into_hex_string_with(self, prefix: bool, pad: bool) -> String;
+}
+
+// every impl checks here first so they all panic the same way
+pub(crate) fn check_radix(radix: u32) {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {}", radix);
+}
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+// `magnitude` in `radix`, after a `-` when `negative` and then `prefix`,
+// padded with zeros to at least `width` digits
+pub(crate) fn write_radix(negative: bool, magnitude: u128, radix: u32, prefix: &str, width: usize) -> String {
+    // 128 is enough for `u128::MAX` in binary
+    let mut digits = [0u8; 128];
+    let mut at = digits.len();
+    let mut rest = magnitude;
+    loop {
+        at -= 1;
+        digits[at] = DIGITS[(rest % u128::from(radix)) as usize];
+        rest /= u128::from(radix);
+        if rest == 0 {
+            break;
+        }
+    }
+    let digits = &digits[at..];
+    let pad = width.saturating_sub(digits.len());
+    let mut out = String::with_capacity(usize::from(negative) + prefix.len() + pad + digits.len());
+    if negative {
+        out.push('-');
+    }
+    out.push_str(prefix);
+    out.extend(core::iter::repeat_n('0', pad));
+    out.extend(digits.iter().map(|x| char::from(*x)));
+    out
+}
+
+int_radix!(unsigned u8, u16, u32, u64, u128, usize);
+int_radix!(signed i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format,vec::Vec};
+
+    // the digits of `x` worked out one at a time, most significant first
+    fn by_hand(mut x: u8, radix: u8) -> String {
+        let mut digits = Vec::new();
+        loop {
+            digits.push(DIGITS[usize::from(x % radix)]);
+            x /= radix;
+            if x == 0 {
+                break;
+            }
+        }
+        digits.iter().rev().map(|x| char::from(*x)).collect()
+    }
+
+    #[test]
+    fn every_u8_in_binary_and_hex() {
+        for x in 0..=u8::MAX {
+            assert_eq!(x.into_string_radix(2), by_hand(x, 2));
+            assert_eq!(x.into_string_radix(16), by_hand(x, 16));
+            assert_eq!(x.into_hex_string(), by_hand(x, 16));
+            assert_eq!((&x).into_string_radix(8), by_hand(x, 8));
+        }
+        assert_eq!(0u8.into_string_radix(2), "0");
+        assert_eq!(u8::MAX.into_string_radix(2), "11111111");
+    }
+
+    #[test]
+    fn signed_values() {
+        assert_eq!(i64::MIN.into_string_radix(16), "-8000000000000000");
+        assert_eq!(i64::MIN.into_hex_string_with(true, true), "-0x8000000000000000");
+        assert_eq!((-255i16).into_string_radix(16), "-ff");
+        assert_eq!((-1i8).into_hex_string_with(true, true), "-0x01");
+        assert_eq!(i128::MIN.into_string_radix(2), format!("-1{}", "0".repeat(127)));
+        assert_eq!(0i32.into_string_radix(36), "0");
+    }
+
+    #[test]
+    fn hex_prefix_and_padding() {
+        assert_eq!(255u16.into_hex_string_with(true, true), "0x00ff");
+        assert_eq!(255u16.into_hex_string_with(true, false), "0xff");
+        assert_eq!(255u16.into_hex_string_with(false, true), "00ff");
+        assert_eq!(1u64.into_hex_string_with(false, true), "0000000000000001");
+        assert_eq!(u128::MAX.into_hex_string_with(false, true), "f".repeat(32));
+        assert_eq!(0usize.into_hex_string_with(true, false), "0x0");
+    }
+
+    #[test]
+    fn radix_36_round_trip() {
+        for x in [0, 1, 35, 36, 1_295, 1_296, i64::MAX, i64::MIN, -42] {
+            let text = x.into_string_radix(36);
+            assert_eq!(i64::from_str_radix(&text, 36), Ok(x), "{text}");
+        }
+        assert_eq!(35u8.into_string_radix(36), "z");
+        assert_eq!(u64::MAX.into_string_radix(36), "3w5e11264sgsf");
+        assert_eq!(u64::from_str_radix(&u64::MAX.into_string_radix(36), 36), Ok(u64::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in 2..=36, got 1")]
+    fn radix_too_small() {
+        5u32.into_string_radix(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in 2..=36, got 37")]
+    fn radix_too_large() {
+        (-5i32).into_string_radix(37);
+    }
+}
@@ -0,0 +1,77 @@
+//! A thread local buffer to convert into, for loops which only need to
+//! look at each value once.
+
+use std::cell::RefCell;
+
+use super::IntoString;
+
+thread_local! {
+    // one buffer per level of nesting, they are handed back when done
+    static SCRATCH: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Converts `value` into a thread local buffer and hands `f` a view of it.
+///
+/// The buffer keeps its capacity between calls, so once it has grown to
+/// fit the longest value nothing is allocated for the types which can be
+/// read in place (`&str`, `&CStr`, `&OsStr` and the like), valid
+/// text is copied straight in. Anything else is converted with its
+/// `IntoString` impl first and then copied, see [`IntoString::push_into`].
+///
+/// Calling `with_scratch` again inside `f` is fine, the nested call gets a
+/// buffer of its own.
+pub fn with_scratch<T: IntoString, R>(value: T, f: impl FnOnce(&str) -> R) -> R {
+    let mut buf = SCRATCH.with(|s| s.borrow_mut().pop()).unwrap_or_default();
+    buf.clear();
+    value.push_into(&mut buf);
+    let out = f(&buf);
+    SCRATCH.with(|s| s.borrow_mut().push(buf));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        ffi::{CString,OsStr},
+    };
+
+    use super::*;
+    use crate::TEST_BYTES;
+
+    #[test]
+    fn same_as_into_string() {
+        for bytes in TEST_BYTES.iter().filter(|x| !x.contains(&0)) {
+            let c = CString::new(*bytes).unwrap();
+            assert_eq!(with_scratch(c.as_c_str(), str::to_owned), c.as_c_str().into_string());
+            assert_eq!(with_scratch(Cow::Borrowed(c.as_c_str()), str::to_owned), c.as_c_str().into_string());
+            assert_eq!(with_scratch(c.clone(), str::to_owned), IntoString::into_string(c));
+        }
+        assert_eq!(with_scratch("str", str::to_owned), "str");
+        assert_eq!(with_scratch(String::from("String"), str::to_owned), "String");
+        assert_eq!(with_scratch(OsStr::new("OsStr"), str::to_owned), "OsStr");
+    }
+
+    #[test]
+    fn buffer_is_kept_between_calls() {
+        let (first, cap) = with_scratch("a longer value to grow the buffer", |s| (s.as_ptr(), s.len()));
+        // shorter values fit in what is already there
+        for value in ["short", "", "a longer value to grow the buffe"] {
+            let (ptr, len) = with_scratch(value, |s| (s.as_ptr(), s.len()));
+            assert_eq!(ptr, first);
+            assert!(len <= cap);
+        }
+    }
+
+    #[test]
+    fn nested_calls_get_their_own_buffer() {
+        let out = with_scratch("outer", |outer| {
+            let inner = with_scratch("inner", |inner| {
+                assert_ne!(inner.as_ptr(), outer.as_ptr());
+                with_scratch("innermost", |s| format!("{inner} {s}"))
+            });
+            format!("{outer} {inner}")
+        });
+        assert_eq!(out, "outer inner innermost");
+    }
+}
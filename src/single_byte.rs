@@ -0,0 +1,307 @@
+//! Single byte encodings, for bytes which were never utf8 to begin with.
+//!
+//! Every byte decodes to exactly one character, so nothing is lost the
+//! way it is when invalid utf8 becomes `U+FFFD`. The mixed mode only uses
+//! them for the bytes which are not utf8, for text where a second writer
+//! spliced Latin-1 into utf8.
+
+use alloc::{
+    ffi::CString,
+    string::String,
+    vec::Vec,
+};
+use core::ffi::CStr;
+
+/// The encoding [`Converter::convert_bytes`](crate::Converter::convert_bytes)
+/// falls back on when the bytes are not valid utf8.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Fallback {
+    /// ISO-8859-1, see [`bytes_into_string_latin1`]
+    Latin1,
+    /// windows-1252, see [`bytes_into_string_cp1252`]
+    Cp1252,
+}
+
+impl Fallback {
+    pub(crate) fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Fallback::Latin1 => bytes_into_string_latin1(bytes),
+            Fallback::Cp1252 => bytes_into_string_cp1252(bytes),
+        }
+    }
+
+    fn decode_byte(self, x: u8) -> char {
+        match self {
+            Fallback::Latin1 => char::from(x),
+            Fallback::Cp1252 => cp1252_char(x),
+        }
+    }
+}
+
+/// Decodes `bytes` as Latin-1 (ISO-8859-1), byte `0xE9` is `é`.
+///
+/// Every byte is the code point of the same value, so this can not fail
+/// or lose anything. Bytes from `0x80` up take two bytes in utf8, the
+/// output is allocated once at its exact length.
+pub fn bytes_into_string_latin1(bytes: &[u8]) -> String {
+    let wide = bytes.iter().filter(|x| !x.is_ascii()).count();
+    let mut out = String::with_capacity(bytes.len() + wide);
+    out.extend(bytes.iter().map(|x| char::from(*x)));
+    out
+}
+
+/// Conversion of byte backed types as Latin-1, see [`bytes_into_string_latin1`].
+pub trait IntoStringLatin1 {
+    fn into_string_latin1(self) -> String;
+}
+
+impl IntoStringLatin1 for &[u8] {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self)
+    }
+}
+impl IntoStringLatin1 for Vec<u8> {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(&self)
+    }
+}
+impl IntoStringLatin1 for &Vec<u8> {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self)
+    }
+}
+impl IntoStringLatin1 for &CStr {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self.to_bytes())
+    }
+}
+impl IntoStringLatin1 for CString {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self.as_bytes())
+    }
+}
+impl IntoStringLatin1 for &CString {
+    fn into_string_latin1(self) -> String {
+        bytes_into_string_latin1(self.as_bytes())
+    }
+}
+
+// `0x80..=0x9F`, the only range where windows-1252 differs from Latin-1
+const CP1252_C1: [char; 32] = [
+    '\u{20AC}', '\u{FFFD}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{FFFD}', '\u{017D}', '\u{FFFD}',
+    '\u{FFFD}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{FFFD}', '\u{017E}', '\u{0178}',
+];
+
+fn cp1252_char(x: u8) -> char {
+    match x {
+        0x80..=0x9F => CP1252_C1[usize::from(x - 0x80)],
+        _ => char::from(x),
+    }
+}
+
+/// Decodes `bytes` as windows-1252, the usual encoding of text from older
+/// Windows tools.
+///
+/// The same as Latin-1 apart from `0x80` to `0x9F`, which hold the euro
+/// sign (`0x80`), curly quotes (`0x93`, `0x94`) and the like. The five bytes
+/// windows-1252 leaves undefined in that range (`0x81`, `0x8D`, `0x8F`,
+/// `0x90`, `0x9D`) become `U+FFFD`, where WHATWG would pass them through as
+/// C1 controls. The output is allocated once at its exact length.
+pub fn bytes_into_string_cp1252(bytes: &[u8]) -> String {
+    let len = bytes.iter().map(|x| cp1252_char(*x).len_utf8()).sum();
+    let mut out = String::with_capacity(len);
+    out.extend(bytes.iter().map(|x| cp1252_char(*x)));
+    out
+}
+
+/// Conversion of byte backed types as windows-1252, see [`bytes_into_string_cp1252`].
+pub trait IntoStringCp1252 {
+    fn into_string_cp1252(self) -> String;
+}
+
+impl IntoStringCp1252 for &[u8] {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(self)
+    }
+}
+impl IntoStringCp1252 for Vec<u8> {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(&self)
+    }
+}
+impl IntoStringCp1252 for &Vec<u8> {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(self)
+    }
+}
+impl IntoStringCp1252 for &CStr {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(self.to_bytes())
+    }
+}
+impl IntoStringCp1252 for CString {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(self.as_bytes())
+    }
+}
+impl IntoStringCp1252 for &CString {
+    fn into_string_cp1252(self) -> String {
+        bytes_into_string_cp1252(self.as_bytes())
+    }
+}
+
+/// Decodes `bytes` as utf8, with every invalid sequence decoded as
+/// `fallback` rather than replaced, `caf\xE9 au lait` is `café au lait`.
+///
+/// Valid utf8 always wins, a byte is only decoded as `fallback` when it
+/// can not be part of a valid sequence. A sequence cut short (`E2 82`
+/// without the last byte) is decoded byte by byte.
+pub fn bytes_into_string_mixed(bytes: &[u8], fallback: Fallback) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        out.push_str(chunk.valid());
+        for x in chunk.invalid() {
+            out.push(fallback.decode_byte(*x));
+        }
+    }
+    out
+}
+
+/// Conversion of byte backed types which are mostly utf8, see
+/// [`bytes_into_string_mixed`].
+pub trait IntoStringMixed {
+    fn into_string_mixed(self, fallback: Fallback) -> String;
+}
+
+impl IntoStringMixed for &[u8] {
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        bytes_into_string_mixed(self, fallback)
+    }
+}
+impl IntoStringMixed for Vec<u8> {
+    /// Special Case
+    ///
+    /// The buffer is kept when it is all valid utf8
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        match String::from_utf8(self) {
+            Ok(x) => x,
+            Err(e) => bytes_into_string_mixed(e.as_bytes(), fallback),
+        }
+    }
+}
+impl IntoStringMixed for &Vec<u8> {
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        bytes_into_string_mixed(self, fallback)
+    }
+}
+impl IntoStringMixed for &CStr {
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        bytes_into_string_mixed(self.to_bytes(), fallback)
+    }
+}
+impl IntoStringMixed for CString {
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        self.into_bytes().into_string_mixed(fallback)
+    }
+}
+impl IntoStringMixed for &CString {
+    fn into_string_mixed(self, fallback: Fallback) -> String {
+        bytes_into_string_mixed(self.as_bytes(), fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn latin1_every_byte() {
+        let bytes: Vec<u8> = (0..=0xFF).collect();
+        let text = bytes_into_string_latin1(&bytes);
+        assert!(text.chars().map(u32::from).eq(0..=0xFF));
+        // 128 ascii bytes and 128 which take two
+        assert_eq!(text.len(), 384);
+        assert_eq!(text.capacity(), 384);
+    }
+
+    #[test]
+    fn latin1_types() {
+        let bytes = vec![b'c', b'a', b'f', 0xE9];
+        assert_eq!((&bytes).into_string_latin1(), "caf\u{e9}");
+        assert_eq!(bytes.as_slice().into_string_latin1(), "caf\u{e9}");
+        let c = CString::new(bytes.clone()).unwrap();
+        assert_eq!(c.as_c_str().into_string_latin1(), "caf\u{e9}");
+        assert_eq!((&c).into_string_latin1(), "caf\u{e9}");
+        assert_eq!(c.into_string_latin1(), "caf\u{e9}");
+        assert_eq!(bytes.into_string_latin1(), "caf\u{e9}");
+        // utf8 is not special, each byte is one char
+        assert_eq!("\u{e9}".as_bytes().into_string_latin1(), "\u{c3}\u{a9}");
+    }
+
+    #[test]
+    fn cp1252_c1_range() {
+        assert_eq!(bytes_into_string_cp1252(b"\x93quoted\x94"), "\u{201C}quoted\u{201D}");
+        assert_eq!(bytes_into_string_cp1252(b"\x80 5"), "\u{20AC} 5");
+        // undefined in windows-1252
+        for x in [0x81, 0x8D, 0x8F, 0x90, 0x9D] {
+            assert_eq!(bytes_into_string_cp1252(&[x]), "\u{FFFD}");
+        }
+        // the rest is Latin-1
+        let bytes: Vec<u8> = (0..0x80).chain(0xA0..=0xFF).collect();
+        assert_eq!(bytes_into_string_cp1252(&bytes), bytes_into_string_latin1(&bytes));
+        let text = bytes_into_string_cp1252(b"\x80\xE9");
+        assert_eq!(text.capacity(), text.len());
+    }
+
+    #[test]
+    fn cp1252_types() {
+        let bytes = vec![0x93, b'q', 0x94];
+        assert_eq!((&bytes).into_string_cp1252(), "\u{201C}q\u{201D}");
+        assert_eq!(bytes.as_slice().into_string_cp1252(), "\u{201C}q\u{201D}");
+        let c = CString::new(bytes.clone()).unwrap();
+        assert_eq!(c.as_c_str().into_string_cp1252(), "\u{201C}q\u{201D}");
+        assert_eq!((&c).into_string_cp1252(), "\u{201C}q\u{201D}");
+        assert_eq!(c.into_string_cp1252(), "\u{201C}q\u{201D}");
+        assert_eq!(bytes.into_string_cp1252(), "\u{201C}q\u{201D}");
+    }
+
+    #[test]
+    fn mixed_utf8_wins() {
+        assert_eq!(bytes_into_string_mixed("caf\u{e9}".as_bytes(), Fallback::Latin1), "caf\u{e9}");
+        assert_eq!(bytes_into_string_mixed(b"caf\xE9 au lait", Fallback::Latin1), "caf\u{e9} au lait");
+        assert_eq!(bytes_into_string_mixed(b"\xE2\x82\xAC \x80", Fallback::Cp1252), "\u{20AC} \u{20AC}");
+        // a sequence cut short is decoded a byte at a time
+        assert_eq!(bytes_into_string_mixed(b"\xE2\x82", Fallback::Latin1), "\u{e2}\u{82}");
+    }
+
+    // utf8 and Latin-1 `é` one after the other
+    #[test]
+    fn mixed_alternating() {
+        let bytes = b"\xC3\xA9\xE9".repeat(4);
+        assert_eq!(bytes_into_string_mixed(&bytes, Fallback::Latin1), "\u{e9}".repeat(8));
+        let bytes = b"\xE9\xC3\xA9".repeat(4);
+        assert_eq!(bytes_into_string_mixed(&bytes, Fallback::Latin1), "\u{e9}".repeat(8));
+        // 0xC3 followed by Latin-1 `é` is not a sequence, both are Latin-1
+        assert_eq!(bytes_into_string_mixed(b"\xC3\xE9", Fallback::Latin1), "\u{c3}\u{e9}");
+    }
+
+    #[test]
+    fn mixed_types() {
+        let bytes = b"caf\xE9".to_vec();
+        assert_eq!((&bytes).into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        assert_eq!(bytes.as_slice().into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        let c = CString::new(bytes.clone()).unwrap();
+        assert_eq!(c.as_c_str().into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        assert_eq!((&c).into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        assert_eq!(c.into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        assert_eq!(bytes.into_string_mixed(Fallback::Latin1), "caf\u{e9}");
+        let valid = "caf\u{e9}".as_bytes().to_vec();
+        let ptr = valid.as_ptr();
+        let text = valid.into_string_mixed(Fallback::Latin1);
+        assert_eq!(text.as_ptr(), ptr);
+    }
+}
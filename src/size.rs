@@ -0,0 +1,117 @@
+//! Byte counts for humans, `1.5 MB` rather than `1536000`.
+
+use alloc::{format,string::String};
+
+use super::{IntoString,IntoStringBase,sealed::Sealed};
+
+/// Which units [`bytes_human_string`] counts in
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub enum SizeStyle {
+    /// Powers of 1000: `kB`, `MB`, `GB`, ... up to `EB`
+    Si,
+    /// Powers of 1024: `KiB`, `MiB`, `GiB`, ... up to `EiB`
+    #[default]
+    Binary,
+}
+
+impl SizeStyle {
+    fn base(self) -> u128 {
+        match self {
+            SizeStyle::Si => 1000,
+            SizeStyle::Binary => 1024,
+        }
+    }
+
+    fn units(self) -> &'static [&'static str; 6] {
+        match self {
+            SizeStyle::Si => &["kB", "MB", "GB", "TB", "PB", "EB"],
+            SizeStyle::Binary => &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"],
+        }
+    }
+}
+
+/// Writes `n` bytes in the largest unit of `style` it reaches, with one
+/// decimal place.
+///
+/// Anything below the first unit is the exact count, `999 B` (SI) or
+/// `1023 B` (binary). The rest is rounded half up, `1536000` is `1.5 MB`
+/// or `1.5 MiB`, and moves on to the next unit when the rounding reaches
+/// it, `1048575` is `1.0 MiB` rather than `1024.0 KiB`. `u64::MAX` is
+/// `18.4 EB` or `16.0 EiB`.
+pub fn bytes_human_string(n: u64, style: SizeStyle) -> String {
+    let base = style.base();
+    let n = u128::from(n);
+    if n < base {
+        return format!("{} B", n);
+    }
+    // u128 so `n * 10` can't overflow, even at `u64::MAX`
+    let mut unit = 0;
+    let mut div = base;
+    let mut tenths = (n * 10 + div / 2) / div;
+    while tenths >= base * 10 && unit + 1 < style.units().len() {
+        unit += 1;
+        div *= base;
+        tenths = (n * 10 + div / 2) / div;
+    }
+    format!("{}.{} {}", tenths / 10, tenths % 10, style.units()[unit])
+}
+
+/// A byte count which converts with [`bytes_human_string`] in
+/// [`SizeStyle::Binary`], for sinks which take an `IntoString`.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct HumanSize(pub u64);
+
+impl IntoString for HumanSize {
+    fn into_string(self) -> String {
+        bytes_human_string(self.0, SizeStyle::Binary)
+    }
+}
+impl Sealed for HumanSize {}
+impl IntoStringBase for HumanSize {
+    fn ref_into_string(&self) -> String {
+        bytes_human_string(self.0, SizeStyle::Binary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_boundaries() {
+        let binary = |n| bytes_human_string(n, SizeStyle::Binary);
+        assert_eq!(binary(0), "0 B");
+        assert_eq!(binary(1023), "1023 B");
+        assert_eq!(binary(1024), "1.0 KiB");
+        assert_eq!(binary(1025), "1.0 KiB");
+        assert_eq!(binary(1536), "1.5 KiB");
+        assert_eq!(binary(1_536_000), "1.5 MiB");
+        // rounds up into the next unit
+        assert_eq!(binary(1_048_575), "1.0 MiB");
+        assert_eq!(binary(1 << 30), "1.0 GiB");
+        assert_eq!(binary(u64::MAX), "16.0 EiB");
+    }
+
+    #[test]
+    fn si_boundaries() {
+        let si = |n| bytes_human_string(n, SizeStyle::Si);
+        assert_eq!(si(999), "999 B");
+        assert_eq!(si(1000), "1.0 kB");
+        assert_eq!(si(1001), "1.0 kB");
+        assert_eq!(si(1023), "1.0 kB");
+        assert_eq!(si(1024), "1.0 kB");
+        assert_eq!(si(1050), "1.1 kB");
+        assert_eq!(si(1_536_000), "1.5 MB");
+        assert_eq!(si(999_950), "1.0 MB");
+        assert_eq!(si(999_949), "999.9 kB");
+        assert_eq!(si(u64::MAX), "18.4 EB");
+    }
+
+    #[test]
+    fn human_size() {
+        assert_eq!(HumanSize(1025).into_string(), "1.0 KiB");
+        assert_eq!((&HumanSize(512)).into_string(), "512 B");
+        assert_eq!(HumanSize::default().into_string(), "0 B");
+        assert_eq!(SizeStyle::default(), SizeStyle::Binary);
+    }
+}
@@ -0,0 +1,41 @@
+//! The counters are process wide, so this is one test in a binary of its
+//! own: `cargo test --features metrics --test metrics`.
+
+use to_string::{ConversionStats,Converter,IntoString,LossyDecoder,conversion_stats,reset_conversion_stats};
+
+fn stats(conversions: u64, lossy: u64, replacements: u64) -> ConversionStats {
+    ConversionStats { conversions, lossy, replacements }
+}
+
+#[test]
+fn exact_counts() {
+    reset_conversion_stats();
+    assert_eq!(conversion_stats(), stats(0, 0, 0));
+
+    // a `str` is never checked
+    assert_eq!("str".into_string(), "str");
+    assert_eq!(conversion_stats(), stats(0, 0, 0));
+
+    assert_eq!(c"valid".into_string(), "valid");
+    assert_eq!(conversion_stats(), stats(1, 0, 0));
+
+    // an invalid `CStr` has every byte replaced
+    assert_eq!(c"a\xFFb".into_string(), "\u{FFFD}".repeat(3));
+    assert_eq!(conversion_stats(), stats(2, 1, 3));
+
+    // two bad sequences, one `U+FFFD` each
+    assert_eq!(Converter::new().convert_bytes(b"x\xFFy\xE2\x82"), "x\u{FFFD}y\u{FFFD}");
+    assert_eq!(conversion_stats(), stats(3, 2, 5));
+
+    // one conversion, lossy once for the bad piece and once for the end
+    let mut decoder = LossyDecoder::new();
+    assert_eq!(decoder.push(b"ok \xFF\xFE"), "ok \u{FFFD}\u{FFFD}");
+    assert_eq!(decoder.push(b"\xE2\x82"), "");
+    assert_eq!(decoder.finish(), "\u{FFFD}");
+    assert_eq!(conversion_stats(), stats(4, 4, 8));
+
+    reset_conversion_stats();
+    assert_eq!(conversion_stats(), stats(0, 0, 0));
+    assert_eq!(Converter::new().convert_bytes(b"\xFF"), "\u{FFFD}");
+    assert_eq!(conversion_stats(), stats(1, 1, 1));
+}
@@ -0,0 +1,31 @@
+//! Everything which is left without `std`, used from a `no_std` crate.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{
+    borrow::Cow,
+    ffi::CString,
+    string::String,
+};
+use core::ffi::CStr;
+
+use to_string::{IntoArrayString,IntoHeaplessString,IntoString};
+
+pub fn borrowed(text: &str, c: &CStr, bytes: &[u8]) -> [String; 4] {
+    [
+        text.into_string(),
+        c.into_string(),
+        Cow::Borrowed(text).into_string(),
+        to_string::bytes_into_string_no_bom(bytes),
+    ]
+}
+
+pub fn owned(text: String, c: CString) -> [String; 2] {
+    [text.into_string(), IntoString::into_string(c)]
+}
+
+pub fn fixed(c: &CStr, n: u32) -> (arrayvec::ArrayString<16>, heapless::String<16>) {
+    (c.into_array_string_truncating(true), n.into_heapless_truncating())
+}
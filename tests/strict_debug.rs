@@ -0,0 +1,91 @@
+//! `cargo test --features strict-debug --test strict_debug`, the panics are
+//! only there with `debug_assertions` (which `cargo test` has, `--release`
+//! checks that it converts as usual without them).
+
+#[cfg(debug_assertions)]
+use std::panic::{self,UnwindSafe};
+
+use to_string::{Converter,IntoString,bytes_into_string_no_bom};
+
+// the message `f` panicked with
+#[cfg(debug_assertions)]
+fn panic_message(f: impl FnOnce() -> String + UnwindSafe) -> String {
+    let err = panic::catch_unwind(f).expect_err("the conversion should have panicked");
+    match err.downcast::<String>() {
+        Ok(x) => *x,
+        Err(err) => err.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn valid_input_converts() {
+    assert_eq!(c"h\xC3\xA9llo".into_string(), "h\u{e9}llo");
+    assert_eq!(Converter::new().convert_bytes(b"valid"), "valid");
+    assert_eq!(bytes_into_string_no_bom(b"\xEF\xBB\xBFvalid"), "valid");
+}
+
+#[cfg(not(debug_assertions))]
+#[test]
+fn release_replaces_as_usual() {
+    assert_eq!(c"abc\xFFdef".into_string(), "\u{FFFD}".repeat(7));
+    assert_eq!(Converter::new().convert_bytes(b"12345\xC3("), "12345\u{FFFD}(");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn panics_with_the_type_and_offset() {
+    let message = panic_message(|| c"abc\xFFdef".into_string());
+    assert!(message.contains("`CStr`"), "{message}");
+    assert!(message.contains("at byte 3"), "{message}");
+    let message = panic_message(|| Converter::new().convert_bytes(b"12345\xC3("));
+    assert!(message.contains("at byte 5"), "{message}");
+    // the offset is in the input, the BOM included
+    let message = panic_message(|| bytes_into_string_no_bom(b"\xEF\xBB\xBFa\xFF"));
+    assert!(message.contains("at byte 4"), "{message}");
+    let message = panic_message(|| to_string::IntoStringNoBom::into_string_no_bom(b"\xEF\xBB\xBFa\xFF".to_vec()));
+    assert!(message.contains("at byte 4"), "{message}");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn decoder_panics_on_a_bad_piece() {
+    let message = panic_message(|| {
+        let mut decoder = to_string::LossyDecoder::new();
+        decoder.push(b"ok");
+        decoder.push(b"ab\xFF").to_string()
+    });
+    assert!(message.contains("at byte 2"), "{message}");
+    // a sequence cut off by the end of the input
+    let message = panic_message(|| {
+        let mut decoder = to_string::LossyDecoder::new();
+        decoder.push(b"\xE2\x82");
+        decoder.finish()
+    });
+    assert!(message.contains("at byte 0"), "{message}");
+}
+
+#[cfg(all(unix, feature = "std", debug_assertions))]
+#[test]
+fn os_str_and_as_lossy_str() {
+    use std::{ffi::OsStr,os::unix::ffi::OsStrExt};
+
+    use to_string::AsLossyStr;
+
+    let name = OsStr::from_bytes(b"name\xFF");
+    let message = panic_message(|| name.into_string());
+    assert!(message.contains("at byte 4"), "{message}");
+    let message = panic_message(|| name.as_lossy_str().into_owned());
+    assert!(message.contains("at byte 4"), "{message}");
+    let message = panic_message(|| to_string::with_scratch(name, str::to_owned));
+    assert!(message.contains("at byte 4"), "{message}");
+}
+
+#[cfg(all(feature = "arrayvec", debug_assertions))]
+#[test]
+fn fixed_capacity_output() {
+    use to_string::IntoArrayString;
+
+    let message = panic_message(|| c"ab\xFF".into_array_string::<8>().unwrap().to_string());
+    assert!(message.contains("at byte 2"), "{message}");
+    assert_eq!(c"ab".into_array_string::<8>().unwrap().as_str(), "ab");
+}
@@ -0,0 +1,102 @@
+//! The warning limit is process wide, so this is one test in a binary of
+//! its own: `cargo test --features tracing --test tracing`.
+
+use std::{
+    fmt,
+    sync::{Arc,Mutex},
+};
+
+use to_string::{Converter,IntoString,DEFAULT_LOSSY_WARNING_LIMIT,set_lossy_warning_limit};
+use tracing::{
+    Event,Level,Subscriber,
+    field::{Field,Visit},
+};
+use tracing_subscriber::{
+    Registry,
+    layer::{Context,Layer,SubscriberExt},
+};
+
+// the fields of an event, in the order they were recorded
+#[derive(Debug,Default,PartialEq)]
+struct Captured {
+    target: String,
+    level: Option<Level>,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for Captured {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.push((field.name().to_string(), value.to_string()));
+    }
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.push((field.name().to_string(), value.to_string()));
+    }
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.push((field.name().to_string(), format!("{value:?}")));
+    }
+}
+
+#[derive(Clone,Default)]
+struct Capture(Arc<Mutex<Vec<Captured>>>);
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut captured = Captured {
+            target: event.metadata().target().to_string(),
+            level: Some(*event.metadata().level()),
+            ..Captured::default()
+        };
+        event.record(&mut captured);
+        self.0.lock().unwrap().push(captured);
+    }
+}
+
+fn event(source: &str, invalid_bytes: usize, preview: &str) -> Captured {
+    let fields = [
+        ("message", "lossy conversion replaced invalid utf8"),
+        ("source", source),
+        ("invalid_bytes", &invalid_bytes.to_string()),
+        ("preview", preview),
+    ];
+    Captured {
+        target: "to_string::lossy".to_string(),
+        level: Some(Level::WARN),
+        fields: fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+    }
+}
+
+#[test]
+fn warnings() {
+    let capture = Capture::default();
+    let events = capture.0.clone();
+    let subscriber = Registry::default().with(capture);
+    tracing::subscriber::with_default(subscriber, || {
+        assert_eq!(c"valid".into_string(), "valid");
+        assert!(events.lock().unwrap().is_empty());
+
+        assert_eq!(Converter::new().convert_bytes(b"a\xFFb\xE2\x82"), "a\u{FFFD}b\u{FFFD}");
+        assert_eq!(*events.lock().unwrap(), [event("[u8]", 3, r"a\xffb\xe2\x82")]);
+        events.lock().unwrap().clear();
+
+        // the preview stops after 64 bytes
+        let mut long = vec![b'x'; 100];
+        long[0] = 0xFF;
+        assert_eq!(Converter::new().convert_bytes(&long).len(), 102);
+        let preview = format!(r"\xff{}...", "x".repeat(63));
+        assert_eq!(*events.lock().unwrap(), [event("[u8]", 1, &preview)]);
+        events.lock().unwrap().clear();
+
+        // only as many as the limit, setting it starts the count again
+        set_lossy_warning_limit(2);
+        for _ in 0..5 {
+            c"\xFF".into_string();
+        }
+        assert_eq!(*events.lock().unwrap(), [event("CStr", 1, r"\xff"), event("CStr", 1, r"\xff")]);
+        set_lossy_warning_limit(0);
+        c"\xFF".into_string();
+        assert_eq!(events.lock().unwrap().len(), 2);
+        set_lossy_warning_limit(DEFAULT_LOSSY_WARNING_LIMIT);
+        c"\xFF".into_string();
+        assert_eq!(events.lock().unwrap().len(), 3);
+    });
+}